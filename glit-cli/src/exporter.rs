@@ -1,5 +1,8 @@
 use colored::Colorize;
-use glit_core::{config::GlobalConfig, org::Org, repo::Repository, user::User};
+use glit_core::{
+    batch::Batch, config::GlobalConfig, crawl::Crawl, listing::Listing, org::Org, repo::Repository,
+    user::User, whois::Whois,
+};
 use serde_json;
 use std::{fs, marker::PhantomData, path::PathBuf, str::FromStr};
 
@@ -12,7 +15,7 @@ impl<T> Exporter<T> {
     pub fn new(global_config: GlobalConfig) -> Self {
         Self {
             global_config,
-            _phantom_data: PhantomData::default(),
+            _phantom_data: PhantomData,
         }
     }
 }
@@ -28,7 +31,7 @@ impl Exporter<Repository> {
                 path.set_file_name("repo.json");
             }
 
-            let json_value = serde_json::to_string_pretty(&data.branch_data).unwrap();
+            let json_value = serde_json::to_string_pretty(data).unwrap();
             fs::write(path.as_path(), json_value).unwrap();
 
             println!("\nResult written in {}", path.to_str().unwrap().yellow());
@@ -73,3 +76,79 @@ impl Exporter<Org> {
         }
     }
 }
+
+impl Exporter<Batch> {
+    pub fn export_batch(self, data: &Batch) {
+        let output = self.global_config.output;
+
+        if !output.is_empty() {
+            let mut path = PathBuf::from_str(&output).unwrap();
+
+            if path.is_dir() {
+                path.set_file_name("batch.json");
+            }
+
+            let json_value = serde_json::to_string_pretty(data).unwrap();
+            fs::write(path.as_path(), json_value).unwrap();
+
+            println!("\nResult written in {}", path.to_str().unwrap().yellow());
+        }
+    }
+}
+
+impl Exporter<Crawl> {
+    pub fn export_crawl(self, data: &Crawl) {
+        let output = self.global_config.output;
+
+        if !output.is_empty() {
+            let mut path = PathBuf::from_str(&output).unwrap();
+
+            if path.is_dir() {
+                path.set_file_name("crawl.json");
+            }
+
+            let json_value = serde_json::to_string_pretty(data).unwrap();
+            fs::write(path.as_path(), json_value).unwrap();
+
+            println!("\nResult written in {}", path.to_str().unwrap().yellow());
+        }
+    }
+}
+
+impl Exporter<Whois> {
+    pub fn export_whois(self, data: &Whois) {
+        let output = self.global_config.output;
+
+        if !output.is_empty() {
+            let mut path = PathBuf::from_str(&output).unwrap();
+
+            if path.is_dir() {
+                path.set_file_name("whois.json");
+            }
+
+            let json_value = serde_json::to_string_pretty(data).unwrap();
+            fs::write(path.as_path(), json_value).unwrap();
+
+            println!("\nResult written in {}", path.to_str().unwrap().yellow());
+        }
+    }
+}
+
+impl Exporter<Listing> {
+    pub fn export_listing(self, data: &Listing) {
+        let output = self.global_config.output;
+
+        if !output.is_empty() {
+            let mut path = PathBuf::from_str(&output).unwrap();
+
+            if path.is_dir() {
+                path.set_file_name("listing.json");
+            }
+
+            let json_value = serde_json::to_string_pretty(data).unwrap();
+            fs::write(path.as_path(), json_value).unwrap();
+
+            println!("\nResult written in {}", path.to_str().unwrap().yellow());
+        }
+    }
+}
@@ -1,5 +1,6 @@
 use clap::ArgMatches;
 use glit_core::config::GlobalConfig;
+use std::path::PathBuf;
 
 pub struct GlobalOptionHandler();
 
@@ -8,7 +9,7 @@ impl GlobalOptionHandler {
         // TODO: get real  number of thread from hardware
         let thread_num = matches
             .get_one::<usize>("thread")
-            .unwrap_or(&(8 as usize))
+            .unwrap_or(&8_usize)
             .to_owned();
 
         let output = matches
@@ -16,6 +17,12 @@ impl GlobalOptionHandler {
             .unwrap_or(&"".to_string())
             .to_owned();
 
-        GlobalConfig { output, thread_num }
+        let ca_cert = matches.get_one::<String>("ca_cert").map(PathBuf::from);
+
+        GlobalConfig {
+            output,
+            thread_num,
+            ca_cert,
+        }
     }
 }
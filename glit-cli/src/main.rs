@@ -3,20 +3,30 @@ pub mod global_option_handler;
 pub mod org_command_handler;
 pub mod printer;
 pub mod repository_command_handler;
+pub mod scan_command_handler;
 pub mod user_command_handler;
 pub mod utils;
+pub mod whois_command_handler;
 use std::{
+    fs, io, process,
     sync::{Arc, Mutex},
     time::Instant,
 };
 
-use clap::{crate_version, Arg, Command};
+use clap::{crate_version, Arg, ArgMatches, Command};
 use colored::Colorize;
 use exporter::Exporter;
 use glit_core::{
+    batch::{Batch, BatchError, Extraction, TargetReport},
+    config::{CrawlConfig, GlobalConfig, OrgConfig, RepositoryConfig, UserConfig},
+    crawl::Crawler,
+    forge::TargetKind,
+    listing::Listing,
     org::{Org, OrgFactory},
     repo::{Repository, RepositoryFactory},
+    tls::{load_ca_certificate, trust_ca_certificate_for_git},
     user::{User, UserFactory},
+    whois::{Whois, GRAVATAR_URL},
     Logger,
 };
 
@@ -25,9 +35,12 @@ use indicatif::MultiProgress;
 use log::LevelFilter;
 use org_command_handler::OrgCommandHandler;
 use repository_command_handler::RepoCommandHandler;
-use reqwest::ClientBuilder;
+use reqwest::{Client, ClientBuilder};
+use scan_command_handler::ScanCommandHandler;
 
 use user_command_handler::UserCommandHandler;
+use utils::crawl_config;
+use whois_command_handler::WhoisCommandHandler;
 
 use crate::printer::Printer;
 
@@ -60,66 +73,240 @@ async fn main() {
                 .num_args(1)
                 .global(true),
         )
+        .arg(
+            Arg::new("ca_cert")
+                .value_name("PEM")
+                .long("ca-cert")
+                .help("Extra CA certificate trusted for API requests and clones (ex: GitHub Enterprise Server)")
+                .num_args(1)
+                .global(true),
+        )
+        .arg(
+            Arg::new("clone_token")
+                .value_name("TOKEN")
+                .long("clone-token")
+                .help("Token used to clone private repositories over HTTPS, only sent to the host of the target (default: the token environment variable of its forge)")
+                .num_args(1)
+                .global(true),
+        )
+        .arg(
+            Arg::new("clone_username")
+                .value_name("USERNAME")
+                .long("clone-username")
+                .help("Username sent with the clone token (default: x-access-token)")
+                .num_args(1)
+                .global(true),
+        )
+        .arg(
+            Arg::new("ssh_key")
+                .value_name("PATH")
+                .long("ssh-key")
+                .help("Private SSH key used to clone, ssh-agent is used otherwise")
+                .num_args(1)
+                .global(true),
+        )
+        .arg(
+            Arg::new("ssh_passphrase")
+                .value_name("PASSPHRASE")
+                .long("ssh-passphrase")
+                .help("Passphrase of the private SSH key")
+                .num_args(1)
+                .global(true),
+        )
 
         .subcommand(
             Command::new("repo")
                 .about("Extract emails from repository")
                 .arg(
-                    Arg::new("repo_url")
+                    Arg::new("url")
                         .value_name("URL")
                         .short('u')
                         .long("url")
                         .help("Github url of a repository"),
                 )
-                .arg(
-                    Arg::new("all_branches")
-                        .short('a')
-                        .long("all-branches")
-                        .help("Get all branch of the repo")
-                        .num_args(0),
-                ),
+                .arg(base_url_arg())
+                .args(log_args()),
         )
         .subcommand(
             Command::new("org")
                 .about("Extract emails from all repositories of a github organisation.")
                 .arg(
-                    Arg::new("org_url")
+                    Arg::new("url")
                         .value_name("URL")
                         .short('u')
                         .long("url")
                         .help("Github url of an organisation."),
                 )
-                .arg(
-                    Arg::new("all_branches")
-                        .short('a')
-                        .long("all-branches")
-                        .help("Get all branch of the repo")
-                        .num_args(0),
-                ),
+                .arg(base_url_arg())
+                .args(forge_args())
+                .args(member_args())
+                .args(selection_args())
+                .args(log_args()),
         )
         .subcommand(
             Command::new("user")
                 .about("Extract emails from all repositories of a user")
                 .arg(
-                    Arg::new("user_url")
+                    Arg::new("url")
                         .value_name("URL")
                         .short('u')
                         .long("url")
                         .help("Github url of a user"),
                 )
+                .arg(base_url_arg())
+                .args(forge_args())
+                .args(selection_args())
+                .arg(
+                    Arg::new("gists")
+                        .long("gists")
+                        .help("Also extract emails from the gists of the user")
+                        .num_args(0),
+                )
+                .arg(
+                    Arg::new("contributions")
+                        .long("contributions")
+                        .help("Also scan the repositories of other owners the user pushed to or had pull requests merged in, keeping only the identities of the user (GitHub REST API)")
+                        .num_args(0),
+                )
+                .args(log_args())
+                .arg(
+                    Arg::new("depth")
+                        .value_name("DEPTH")
+                        .long("depth")
+                        .help("Also scan the followers, followed users and organizations of the user, and theirs, up to DEPTH hops away")
+                        .value_parser(clap::value_parser!(usize))
+                        .conflicts_with("dry_run")
+                        .num_args(1),
+                )
+                .arg(
+                    Arg::new("max_targets")
+                        .value_name("COUNT")
+                        .long("max-targets")
+                        .help("Stop the crawl after scanning COUNT users and organizations")
+                        .value_parser(clap::value_parser!(usize))
+                        .default_value("50")
+                        .requires("depth")
+                        .num_args(1),
+                )
+                .arg(
+                    Arg::new("scope_owner")
+                        .value_name("GLOB")
+                        .long("scope-owner")
+                        .help("Only crawl accounts whose login matches the glob (can be repeated)")
+                        .requires("depth")
+                        .action(clap::ArgAction::Append),
+                ),
+        )
+        .subcommand(
+            Command::new("scan")
+                .about("Detect whether the url is a repository, a user or an organisation and extract emails accordingly")
+                .arg(
+                    Arg::new("url")
+                        .value_name("URL")
+                        .short('u')
+                        .long("url")
+                        .help("Url of a repository, a user or an organisation"),
+                )
+                .arg(
+                    Arg::new("targets")
+                        .value_name("FILE")
+                        .long("targets")
+                        .help("Scan every url of a newline separated list, - reads the list from stdin")
+                        .conflicts_with("url")
+                        .num_args(1),
+                )
+                .arg(base_url_arg())
+                .args(forge_args())
+                .args(member_args())
+                .args(selection_args())
+                .arg(
+                    Arg::new("gists")
+                        .long("gists")
+                        .help("Also extract emails from the gists of the user, when the url is a user")
+                        .num_args(0),
+                )
+                .arg(
+                    Arg::new("contributions")
+                        .long("contributions")
+                        .help("Also scan the repositories of other owners the user contributed to, when the url is a user")
+                        .num_args(0),
+                )
+                .args(log_args()),
+        )
+        .subcommand(
+            Command::new("whois")
+                .about("Find the GitHub accounts, repositories and commits of an email with the commit search")
+                .arg(
+                    Arg::new("email")
+                        .value_name("EMAIL")
+                        .help("Author email to look up")
+                        .required(true),
+                )
+                .arg(
+                    Arg::new("base_url")
+                        .value_name("URL")
+                        .long("base-url")
+                        .help("Web url of a GitHub Enterprise Server (default: github.com)")
+                        .num_args(1),
+                )
+                .arg(
+                    Arg::new("api_url")
+                        .value_name("URL")
+                        .long("api-url")
+                        .help("Base url of the REST API (default: deduced from --base-url)")
+                        .num_args(1),
+                )
+                .arg(
+                    Arg::new("token")
+                        .long("token")
+                        .help("API token (default: GITHUB_TOKEN environment variable)")
+                        .num_args(1),
+                )
+                .arg(
+                    Arg::new("max_commits")
+                        .value_name("COUNT")
+                        .long("max-commits")
+                        .help("Search at most COUNT commits, the search stops at 1000")
+                        .value_parser(clap::value_parser!(usize))
+                        .default_value("100")
+                        .num_args(1),
+                )
                 .arg(
-                    Arg::new("all_branches")
-                        .short('a')
-                        .long("all-branches")
-                        .help("Get all branch of the repo")
+                    Arg::new("samples")
+                        .value_name("COUNT")
+                        .long("samples")
+                        .help("Sample commits reported for each account")
+                        .value_parser(clap::value_parser!(usize))
+                        .default_value("3")
+                        .num_args(1),
+                )
+                .arg(
+                    Arg::new("gravatar")
+                        .long("gravatar")
+                        .help("Also fetch the gravatar profile of the email and flag the accounts it links to")
                         .num_args(0),
+                )
+                .arg(
+                    Arg::new("gravatar_url")
+                        .value_name("URL")
+                        .long("gravatar-url")
+                        .help("Base url of the gravatar profiles")
+                        .default_value(GRAVATAR_URL)
+                        .num_args(1),
                 ),
         )
         .get_matches();
 
-    let client = ClientBuilder::new().build().unwrap();
     let global_config = GlobalOptionHandler::config(&matches);
 
+    let mut client_builder = ClientBuilder::new().user_agent(concat!("glit/", crate_version!()));
+    if let Some(ca_cert) = &global_config.ca_cert {
+        let certificate = load_ca_certificate(ca_cert)
+            .unwrap_or_else(|e| panic!("Invalid --ca-cert {:?} : {}", ca_cert, e));
+        client_builder = client_builder.add_root_certificate(certificate);
+    }
+    let client = client_builder.build().unwrap();
+
     let verbose = matches.get_count("verbose").to_owned();
 
     let level = match verbose {
@@ -131,6 +318,17 @@ async fn main() {
     };
 
     env_logger::builder().filter_level(level).init();
+
+    // Only libgit2 built with OpenSSL accepts a certificate location, API requests still trust it
+    if let Some(ca_cert) = &global_config.ca_cert {
+        if let Err(e) = trust_ca_certificate_for_git(ca_cert) {
+            log::warn!(
+                "Clones cannot trust --ca-cert {:?}, they use the system certificates : {}",
+                ca_cert,
+                e
+            );
+        }
+    }
     log::info!(
         "Brought to you by {} - {}",
         "@shadawck".bright_purple(),
@@ -138,58 +336,411 @@ async fn main() {
     );
 
     match matches.subcommand() {
-        Some(("repo", sub_match)) => {
-            let time = Instant::now();
-            let repository_config = RepoCommandHandler::config(sub_match);
+        Some(("repo", sub_match)) => run_repo(sub_match, global_config),
+        Some(("user", sub_match)) => run_user(sub_match, &client, global_config).await,
+        Some(("org", sub_match)) => run_org(sub_match, &client, global_config).await,
+        Some(("whois", sub_match)) => run_whois(sub_match, &client, global_config).await,
+        Some(("scan", sub_match)) => match sub_match.get_one::<String>("targets") {
+            Some(targets) => {
+                let batch = run_batch(sub_match, targets, &client, global_config).await;
+                if batch.failed_count() > 0 {
+                    process::exit(1);
+                }
+            }
+            None => {
+                let url = sub_match.get_one::<String>("url").unwrap();
+                let kind = ScanCommandHandler::target_kind(sub_match, url, &client)
+                    .await
+                    .unwrap_or_else(|e| panic!("Failed to detect the kind of {} : {}", url, e));
+                match kind {
+                    TargetKind::Repository => run_repo(sub_match, global_config),
+                    TargetKind::User => run_user(sub_match, &client, global_config).await,
+                    TargetKind::Org => run_org(sub_match, &client, global_config).await,
+                }
+            }
+        },
+        _ => {}
+    }
+}
 
-            let mpb: Arc<Mutex<MultiProgress>> = Arc::new(Mutex::new(MultiProgress::new()));
+fn run_repo(sub_match: &ArgMatches, global_config: GlobalConfig) {
+    let time = Instant::now();
+    let repository_config = RepoCommandHandler::config(sub_match);
+    if dry_run(sub_match) {
+        let listing = Listing::repository(&repository_config.url);
+        return report_listing(&listing, global_config, time);
+    }
 
-            let repository = RepositoryFactory::with_config(repository_config).create(mpb);
-            let repo_extraction = repository.extract_log();
+    let repo_extraction = extract_repo(repository_config);
 
-            let printer = Printer::<Repository>::new(global_config.clone());
-            printer.print_repo(&repo_extraction);
+    let printer = Printer::<Repository>::new(global_config.clone());
+    printer.print_repo(&repo_extraction);
 
-            let exporter = Exporter::new(global_config);
-            exporter.export_repo(&repo_extraction);
+    let exporter = Exporter::new(global_config);
+    exporter.export_repo(&repo_extraction);
 
-            log::info!("Done in {:?}", time.elapsed());
-        }
-        Some(("user", sub_match)) => {
-            let time = Instant::now();
-            let user_config = UserCommandHandler::config(sub_match);
-            let user: User = UserFactory::with_config(user_config)
-                .build_with_client(&client)
-                .await;
+    log::info!("Done in {:?}", time.elapsed());
+}
 
-            let user_with_log = Logger::log_for(user, &client).await;
+async fn run_user(sub_match: &ArgMatches, client: &Client, global_config: GlobalConfig) {
+    let time = Instant::now();
+    let user_config = UserCommandHandler::config(sub_match);
+    if let Some(crawl_config) = crawl_config(sub_match) {
+        return run_crawl(user_config, crawl_config, client, global_config, time).await;
+    }
+    if dry_run(sub_match) {
+        let listing = list_user(user_config, client).await.listing();
+        return report_listing(&listing, global_config, time);
+    }
 
-            let printer = Printer::new(global_config.clone());
-            printer.print_user(&user_with_log);
+    let user_with_log = extract_user(user_config, client).await;
 
-            let exporter = Exporter::new(global_config.clone());
-            exporter.export_user(&user_with_log);
+    let printer = Printer::new(global_config.clone());
+    printer.print_user(&user_with_log);
 
-            log::info!("Done in {:?}", time.elapsed());
-        }
-        Some(("org", sub_match)) => {
-            let time = Instant::now();
+    let exporter = Exporter::new(global_config);
+    exporter.export_user(&user_with_log);
+
+    log::info!("Done in {:?}", time.elapsed());
+}
+
+async fn run_org(sub_match: &ArgMatches, client: &Client, global_config: GlobalConfig) {
+    let time = Instant::now();
+    let org_config = OrgCommandHandler::config(sub_match);
+    if dry_run(sub_match) {
+        let listing = list_org(org_config, client).await.listing();
+        return report_listing(&listing, global_config, time);
+    }
+
+    let org_with_log = extract_org(org_config, client).await;
+
+    let printer = Printer::new(global_config.clone());
+    printer.print_org(&org_with_log);
+
+    let exporter = Exporter::new(global_config);
+    exporter.export_org(&org_with_log);
+
+    log::info!("Done in {:?}", time.elapsed());
+}
+
+async fn run_whois(sub_match: &ArgMatches, client: &Client, global_config: GlobalConfig) {
+    let time = Instant::now();
+    let whois_config = WhoisCommandHandler::config(sub_match);
+
+    let whois = Whois::lookup(client, &whois_config)
+        .await
+        .unwrap_or_else(|e| {
+            panic!(
+                "Failed to search the commits of {} : {}",
+                whois_config.email, e
+            )
+        });
+
+    let printer = Printer::new(global_config.clone());
+    printer.print_whois(&whois);
+
+    let exporter = Exporter::new(global_config);
+    exporter.export_whois(&whois);
+
+    log::info!("Done in {:?}", time.elapsed());
+}
+
+/// Scan the user, then the accounts reached from it
+async fn run_crawl(
+    user_config: UserConfig,
+    crawl_config: CrawlConfig,
+    client: &Client,
+    global_config: GlobalConfig,
+    time: Instant,
+) {
+    let crawl = Crawler::new(user_config, crawl_config).run(client).await;
+
+    let printer = Printer::new(global_config.clone());
+    printer.print_crawl(&crawl);
+
+    let exporter = Exporter::new(global_config);
+    exporter.export_crawl(&crawl);
+
+    log::info!("Done in {:?}", time.elapsed());
+}
+
+/// Scan every target of a list with the same client and extraction pool.
+/// A failing target is only reported, the caller decides the exit status.
+async fn run_batch(
+    sub_match: &ArgMatches,
+    targets: &str,
+    client: &Client,
+    global_config: GlobalConfig,
+) -> Batch {
+    let time = Instant::now();
 
-            let org_config = OrgCommandHandler::config(sub_match);
-            let org: Org = OrgFactory::with_config(org_config)
-                .build_with_client(&client)
-                .await;
+    let list = match targets {
+        "-" => io::read_to_string(io::stdin()).expect("Failed to read targets from stdin"),
+        path => fs::read_to_string(path)
+            .unwrap_or_else(|e| panic!("Failed to read targets from {} : {}", path, e)),
+    };
 
-            let org_with_log = Logger::log_for(org, &client).await;
+    let mut batch = Batch::default();
+    for target in Batch::parse_targets(&list) {
+        let report = match scan_target(sub_match, &target, client).await {
+            Ok(extraction) => TargetReport::done(extraction),
+            Err(e) => {
+                log::warn!("Skip target {} : {}", target, e);
+                TargetReport::failed(e.to_string())
+            }
+        };
+        batch.insert(target, report);
+    }
 
-            let printer = Printer::new(global_config.clone());
-            printer.print_org(&org_with_log);
+    let printer = Printer::new(global_config.clone());
+    printer.print_batch(&batch);
 
-            let exporter = Exporter::new(global_config.clone());
-            exporter.export_org(&org_with_log);
+    let exporter = Exporter::new(global_config);
+    exporter.export_batch(&batch);
 
-            log::info!("Done in {:?}", time.elapsed());
+    log::info!("Done in {:?}", time.elapsed());
+    batch
+}
+
+async fn scan_target(
+    sub_match: &ArgMatches,
+    url: &str,
+    client: &Client,
+) -> Result<Extraction, BatchError> {
+    let kind = ScanCommandHandler::target_kind(sub_match, url, client).await?;
+    if dry_run(sub_match) {
+        let listing = match kind {
+            TargetKind::Repository => {
+                Listing::repository(&RepoCommandHandler::config_for(sub_match, url)?.url)
+            }
+            TargetKind::User => {
+                let user_config = UserCommandHandler::config_for(sub_match, url)?;
+                UserFactory::with_config(user_config)
+                    .try_build_with_client(client)
+                    .await?
+                    .listing()
+            }
+            TargetKind::Org => {
+                let org_config = OrgCommandHandler::config_for(sub_match, url)?;
+                OrgFactory::with_config(org_config)
+                    .try_build_with_client(client)
+                    .await?
+                    .listing()
+            }
+        };
+        return Ok(Extraction::Listing(listing));
+    }
+
+    match kind {
+        TargetKind::Repository => {
+            let mpb = Arc::new(Mutex::new(MultiProgress::new()));
+            let repository_config = RepoCommandHandler::config_for(sub_match, url)?;
+            let repository = RepositoryFactory::with_config(repository_config).create(mpb)?;
+            Ok(Extraction::Repository(repository.extract_log()))
+        }
+        TargetKind::User => {
+            let user_config = UserCommandHandler::config_for(sub_match, url)?;
+            let user = UserFactory::with_config(user_config)
+                .try_build_with_client(client)
+                .await?;
+            Ok(Extraction::User(Logger::log_for(user, client).await))
+        }
+        TargetKind::Org => {
+            let org_config = OrgCommandHandler::config_for(sub_match, url)?;
+            let org = OrgFactory::with_config(org_config)
+                .try_build_with_client(client)
+                .await?;
+            Ok(Extraction::Org(Box::new(
+                Logger::log_for(org, client).await,
+            )))
         }
-        _ => {}
     }
 }
+
+fn extract_repo(repository_config: RepositoryConfig) -> Repository {
+    let mpb: Arc<Mutex<MultiProgress>> = Arc::new(Mutex::new(MultiProgress::new()));
+
+    let url = repository_config.url.clone();
+    let repository = RepositoryFactory::with_config(repository_config)
+        .create(mpb)
+        .unwrap_or_else(|e| panic!("Failed to clone {} : {}", url, e.message()));
+    repository.extract_log()
+}
+
+/// Listing phase only, repositories are not cloned yet
+async fn list_user(user_config: UserConfig, client: &Client) -> User {
+    UserFactory::with_config(user_config)
+        .build_with_client(client)
+        .await
+}
+
+async fn extract_user(user_config: UserConfig, client: &Client) -> User {
+    let user = list_user(user_config, client).await;
+    Logger::log_for(user, client).await
+}
+
+/// Listing phase only, repositories are not cloned yet
+async fn list_org(org_config: OrgConfig, client: &Client) -> Org {
+    OrgFactory::with_config(org_config)
+        .build_with_client(client)
+        .await
+}
+
+async fn extract_org(org_config: OrgConfig, client: &Client) -> Org {
+    let org = list_org(org_config, client).await;
+    Logger::log_for(org, client).await
+}
+
+fn report_listing(listing: &Listing, global_config: GlobalConfig, time: Instant) {
+    let printer = Printer::new(global_config.clone());
+    printer.print_listing(listing);
+
+    let exporter = Exporter::new(global_config);
+    exporter.export_listing(listing);
+
+    log::info!("Done in {:?}", time.elapsed());
+}
+
+/// `--base-url` of the subcommands taking a target url
+fn base_url_arg() -> Arg {
+    Arg::new("base_url")
+        .value_name("URL")
+        .long("base-url")
+        .help("Web url of a GitHub Enterprise Server, --url can then be a path like owner or owner/repository")
+        .num_args(1)
+}
+
+/// How the forge of a user or an organization is reached
+fn forge_args() -> Vec<Arg> {
+    vec![
+        Arg::new("forge")
+            .long("forge")
+            .help("Forge hosting the target (default: deduced from the host, github otherwise)")
+            .value_parser(["github", "gitlab", "gitea", "bitbucket", "sourcehut"])
+            .num_args(1),
+        Arg::new("backend")
+            .long("backend")
+            .help("How github repositories are listed : scraping github pages, the REST API or the GraphQL API. Defaults to html on github.com and api on GitHub Enterprise Server. Other forges always use their API.")
+            .value_parser(["html", "api", "graphql"])
+            .num_args(1),
+        Arg::new("api_url")
+            .value_name("URL")
+            .long("api-url")
+            .help("Base url of the REST API (default: deduced from the target url)")
+            .num_args(1),
+        Arg::new("api_username")
+            .value_name("USERNAME")
+            .long("api-username")
+            .help("Username of a Bitbucket app password given with --token")
+            .num_args(1),
+        Arg::new("token")
+            .long("token")
+            .help("API token (default: GITHUB_TOKEN, GITLAB_TOKEN, GITEA_TOKEN, BITBUCKET_TOKEN or SRHT_TOKEN environment variable)")
+            .num_args(1),
+    ]
+}
+
+/// Members of an organization
+fn member_args() -> Vec<Arg> {
+    vec![
+        Arg::new("members")
+            .long("members")
+            .help("List the public members of the organisation")
+            .num_args(0),
+        Arg::new("expand_members")
+            .long("expand-members")
+            .help("Also scan the repositories of each public member of the organisation, reported under the organisation")
+            .num_args(0),
+    ]
+}
+
+/// Which listed repositories of a user or an organization are scanned
+fn selection_args() -> Vec<Arg> {
+    vec![
+        Arg::new("include_forks")
+            .long("include-forks")
+            .help("Also scan forked repositories")
+            .num_args(0),
+        Arg::new("exclude_archived")
+            .long("exclude-archived")
+            .help("Skip archived repositories")
+            .num_args(0),
+        Arg::new("only_mirrors")
+            .long("only-mirrors")
+            .help("Only scan repositories mirrored from another host")
+            .num_args(0),
+        Arg::new("repo_include")
+            .value_name("PATTERN")
+            .long("repo-include")
+            .help("Only scan repositories whose name matches the glob, or the regex after re: (can be repeated)")
+            .action(clap::ArgAction::Append),
+        Arg::new("repo_exclude")
+            .value_name("PATTERN")
+            .long("repo-exclude")
+            .help("Skip repositories whose name matches the glob, or the regex after re: (can be repeated)")
+            .action(clap::ArgAction::Append),
+        Arg::new("max_repos")
+            .value_name("COUNT")
+            .long("max-repos")
+            .help("Scan at most COUNT repositories")
+            .value_parser(clap::value_parser!(usize))
+            .num_args(1),
+        Arg::new("dry_run")
+            .long("dry-run")
+            .visible_alias("list-repos")
+            .help("Only list the repositories that would be scanned, nothing is cloned")
+            .num_args(0),
+        Arg::new("max_repo_size")
+            .value_name("KB")
+            .long("max-repo-size")
+            .help("Skip repositories larger than KB kilobytes, when the listing gives their size")
+            .value_parser(clap::value_parser!(u64))
+            .num_args(1),
+    ]
+}
+
+/// What is extracted from each cloned repository
+fn log_args() -> Vec<Arg> {
+    vec![
+        Arg::new("all_branches")
+            .short('a')
+            .long("all-branches")
+            .help("Get all branch of the repo")
+            .num_args(0),
+        Arg::new("path")
+            .value_name("GLOB")
+            .short('p')
+            .long("path")
+            .help("Only keep commits touching paths matching the glob (can be repeated)")
+            .action(clap::ArgAction::Append),
+        Arg::new("ownership")
+            .long("ownership")
+            .help("Map the directories, file extensions and lines touched by each author")
+            .num_args(0),
+        Arg::new("blame")
+            .long("blame")
+            .help("Count the lines of current code owned by each author on the default branch")
+            .num_args(0),
+        Arg::new("submodules")
+            .value_name("DEPTH")
+            .long("submodules")
+            .help("Follow git submodules recursively up to DEPTH levels")
+            .value_parser(clap::value_parser!(usize))
+            .num_args(1),
+        Arg::new("wiki")
+            .long("wiki")
+            .help("Also extract emails from the wiki of each repository")
+            .num_args(0),
+        Arg::new("max_commits")
+            .value_name("COUNT")
+            .long("max-commits-per-repo")
+            .help("Only walk the COUNT most recent commits of each repository, across all branches")
+            .value_parser(clap::value_parser!(usize))
+            .num_args(1),
+    ]
+}
+
+/// `--dry-run` only exists on subcommands listing repositories
+fn dry_run(sub_match: &ArgMatches) -> bool {
+    matches!(sub_match.try_get_one::<bool>("dry_run"), Ok(Some(true)))
+}
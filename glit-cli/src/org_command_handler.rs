@@ -1,28 +1,58 @@
 use clap::ArgMatches;
-use glit_core::config::OrgConfig;
-use reqwest::Url;
+use glit_core::{
+    config::OrgConfig,
+    target::{Target, TargetError},
+};
 
-use crate::utils::fix_input_url;
+use crate::utils::{
+    api_config, backend, forge, listing_filter, log_config, selection, target_input,
+};
 
 pub struct OrgCommandHandler {}
 
 impl OrgCommandHandler {
     pub fn config(subcommand_match: &ArgMatches) -> OrgConfig {
-        let org_url = subcommand_match
-            .get_one::<String>("org_url")
-            .unwrap()
-            .as_str();
+        let org_url = subcommand_match.get_one::<String>("url").unwrap();
+        Self::config_for(subcommand_match, org_url)
+            .unwrap_or_else(|e| panic!("Invalid org url : {}", e))
+    }
 
+    /// Config of one target, the other options are read from the subcommand
+    pub fn config_for(
+        subcommand_match: &ArgMatches,
+        org_url: &str,
+    ) -> Result<OrgConfig, TargetError> {
         let all_branches = subcommand_match
             .get_one::<bool>("all_branches")
             .unwrap()
             .to_owned();
 
-        let org_url = fix_input_url(org_url);
+        // file:// targets have no user or organization page
+        let target = Target::owner(&target_input(subcommand_match, org_url))?;
+        let url = target
+            .web_url()
+            .ok_or_else(|| TargetError::UnsupportedScheme(target.scheme.clone()))?;
+
+        let forge = forge(subcommand_match, &url);
+        let log_config = log_config(subcommand_match, forge, &url);
+        let backend = backend(subcommand_match, &url);
+        let api_config = api_config(subcommand_match, forge, &url);
+        let listing_filter = listing_filter(subcommand_match);
+        let selection = selection(subcommand_match);
+        let members = subcommand_match.get_flag("members");
+        let expand_members = subcommand_match.get_flag("expand_members");
 
-        OrgConfig {
-            url: Url::parse(&org_url).unwrap(),
+        Ok(OrgConfig {
+            url,
             all_branches,
-        }
+            log_config,
+            forge,
+            backend,
+            api_config,
+            listing_filter,
+            selection,
+            members,
+            expand_members,
+        })
     }
 }
@@ -1,7 +1,22 @@
 use colored::Colorize;
-use glit_core::{config::GlobalConfig, org::Org, repo::Repository, user::User};
+use glit_core::{
+    batch::{Batch, Extraction, TargetReport, TargetStatus},
+    blame::Blame,
+    config::GlobalConfig,
+    crawl::Crawl,
+    listing::Listing,
+    org::Org,
+    ownership::Ownership,
+    repo::Repository,
+    selection::SkippedRepository,
+    user::User,
+    whois::{SampleCommit, Whois},
+};
 use std::marker::PhantomData;
 
+const TOP_OWNERSHIP_ENTRIES: usize = 3;
+const GITHUB_NOREPLY_DOMAIN: &str = "noreply.github.com";
+
 pub struct Printer<T> {
     global_config: GlobalConfig,
     data: PhantomData<T>,
@@ -11,23 +26,63 @@ impl<T> Printer<T> {
     pub fn new(global_config: GlobalConfig) -> Self {
         Self {
             global_config,
-            data: PhantomData::default(),
+            data: PhantomData,
         }
     }
 }
 
 impl Printer<Repository> {
     pub fn print_repo(&self, data: &Repository) {
+        let host = data.host();
+        let path_scoped = !data.paths.is_empty();
+        if path_scoped {
+            let paths_format = format!("[ Paths : {} ]", data.paths.join(", ")).cyan();
+            println!("{}", paths_format);
+        }
+
         for (branch, value) in &data.branch_data {
-            let branch_format = format!("[ Branch : {} ]", branch.to_string()).yellow();
+            let branch_format = format!("[ Branch : {} ]", branch).yellow();
             println!("{}", branch_format);
             for (author, data) in &value.committers {
                 let mails = data.mails.keys().cloned().collect::<Vec<String>>();
-                println!("{}:", author.to_string().trim().blue());
+                if path_scoped {
+                    let commit_count: usize = data.mails.values().map(Vec::len).sum();
+                    println!(
+                        "{}: {}",
+                        author.to_string().trim().blue(),
+                        format!("({} matching commits)", commit_count).cyan()
+                    );
+                } else {
+                    println!("{}:", author.to_string().trim().blue());
+                }
 
-                print_mail(mails, author.to_string().trim());
+                print_mail(mails, author.to_string().trim(), host);
+
+                if let Some(ownership) = &data.ownership {
+                    print_ownership(ownership);
+                }
             }
         }
+
+        if let Some(blame) = &data.blame {
+            print_blame(blame, host);
+        }
+
+        for (submodule_path, submodule) in &data.submodules {
+            let submodule_format = format!(
+                "[ Submodule : {} ({}/{}) ]",
+                submodule_path, submodule.owner, submodule.name
+            )
+            .magenta();
+            println!("{}", submodule_format);
+            self.print_repo(submodule);
+        }
+
+        if let Some(wiki) = &data.wiki {
+            let wiki_format = format!("[ Wiki : {}/{} ]", wiki.owner, wiki.name).magenta();
+            println!("{}", wiki_format);
+            self.print_repo(wiki);
+        }
     }
 }
 
@@ -35,44 +90,333 @@ impl Printer<User> {
     pub fn print_user(&self, data: &User) {
         let printer = Printer::new(self.global_config.clone());
         for (repo_name, value) in data.repositories_data.clone() {
-            let repo_format = format!("[ Repository : {} ]", repo_name.to_string()).magenta();
-            println!("{}", repo_format);
+            print_repository_header(&repo_name.to_string(), &value);
             printer.print_repo(&value);
         }
+
+        if !data.contributions_data.is_empty() {
+            let contributions_format = format!(
+                "[ Contributions : {} repositories ]",
+                data.contributions_data.len()
+            )
+            .bright_cyan();
+            println!("{}", contributions_format);
+        }
+        for (repo_name, value) in data.contributions_data.clone() {
+            print_repository_header(&repo_name.to_string(), &value);
+            printer.print_repo(&value);
+        }
+        print_skipped(&data.skipped_repositories);
     }
 }
 impl Printer<Org> {
     pub fn print_org(&self, data: &Org) {
         let printer = Printer::new(self.global_config.clone());
         for (repo_name, value) in data.repositories_data.clone() {
-            let repo_format = format!("[ Repository : {} ]", repo_name.to_string()).magenta();
-            println!("{}", repo_format);
+            print_repository_header(&repo_name.to_string(), &value);
             printer.print_repo(&value);
         }
+        print_skipped(&data.skipped_repositories);
+
+        if !data.members.is_empty() {
+            let members_format = format!(
+                "[ Members : {} ({}) ]",
+                data.members.join(", "),
+                data.members.len()
+            )
+            .bright_cyan();
+            println!("{}", members_format);
+        }
+
+        let user_printer = Printer::new(self.global_config.clone());
+        for (login, member) in &data.members_data {
+            let member_format = format!("[ Member : {} ]", login).bright_cyan();
+            println!("{}", member_format);
+            user_printer.print_user(member);
+        }
     }
 }
 
-fn print_mail(mails: Vec<String>, author: &str) {
+impl Printer<Batch> {
+    pub fn print_batch(&self, data: &Batch) {
+        for (target, report) in &data.targets {
+            let target_format = format!("[ Target : {} ]", target).bright_cyan();
+            println!("{}", target_format);
+            print_report(&self.global_config, report);
+        }
+
+        let summary = format!(
+            "{}/{} targets done",
+            data.targets.len() - data.failed_count(),
+            data.targets.len()
+        );
+        println!("\n{}", summary.yellow());
+    }
+}
+
+impl Printer<Crawl> {
+    pub fn print_crawl(&self, data: &Crawl) {
+        for (login, target) in &data.targets {
+            let kind = target
+                .report
+                .kind
+                .map(|kind| format!("{:?}", kind).to_lowercase())
+                .unwrap_or_default();
+            let target_format =
+                format!("[ Target : {} ({}, depth {}) ]", login, kind, target.depth).bright_cyan();
+            println!("{}", target_format);
+            print_report(&self.global_config, &target.report);
+        }
+
+        if !data.edges.is_empty() {
+            println!("{}", "[ Reached through ]".bright_cyan());
+        }
+        for edge in &data.edges {
+            let relation = format!("{:?}", edge.relation).to_lowercase();
+            println!(
+                "{} -> {} ({})",
+                edge.from,
+                edge.to.blue(),
+                relation.dimmed()
+            );
+        }
+
+        let summary = format!("{} targets scanned from {}", data.targets.len(), data.root);
+        println!("\n{}", summary.yellow());
+    }
+}
+
+impl Printer<Whois> {
+    pub fn print_whois(&self, data: &Whois) {
+        let whois_format =
+            format!("[ Whois : {} - {} commits ]", data.email, data.commit_count).yellow();
+        println!("{}", whois_format);
+
+        if !data.author_names.is_empty() {
+            let names = data
+                .author_names
+                .iter()
+                .cloned()
+                .collect::<Vec<String>>()
+                .join(", ");
+            println!("Author names : {}", names.blue());
+        }
+
+        for account in &data.accounts {
+            let gravatar = match account.gravatar_linked {
+                true => " (linked by gravatar)",
+                false => "",
+            };
+            let account_format = format!(
+                "[ Account : {} - {} commits{} ]",
+                account.login, account.commit_count, gravatar
+            )
+            .bright_cyan();
+            println!("{}", account_format);
+            println!("{}", account.url);
+
+            let repositories = account
+                .repositories
+                .iter()
+                .cloned()
+                .collect::<Vec<String>>()
+                .join(", ");
+            println!("Repositories : {}", repositories.blue());
+            print_sample_commits(&account.sample_commits);
+        }
+
+        if !data.unattached_commits.is_empty() {
+            println!("{}", "[ Commits attached to no account ]".bright_cyan());
+            print_sample_commits(&data.unattached_commits);
+        }
+
+        if let Some(gravatar) = &data.gravatar {
+            let gravatar_format = format!("[ Gravatar : {} ]", gravatar.hash).bright_cyan();
+            println!("{}", gravatar_format);
+            if let Some(profile_url) = &gravatar.profile_url {
+                println!("{}", profile_url);
+            }
+            for linked in &gravatar.accounts {
+                println!("{} {}", linked.domain.blue(), linked.username);
+            }
+        }
+    }
+}
+
+impl Printer<Listing> {
+    pub fn print_listing(&self, data: &Listing) {
+        let listing_format = format!(
+            "[ Listing : {} - {} repositories ]",
+            data.owner,
+            data.repositories.len()
+        )
+        .yellow();
+        println!("{}", listing_format);
+
+        for repository in &data.repositories {
+            let metadata = &repository.metadata;
+            let details = [
+                metadata
+                    .default_branch
+                    .as_ref()
+                    .map(|branch| format!("branch {}", branch)),
+                metadata.size_kb.map(|size| format!("{} KB", size)),
+                metadata.fork.then(|| "fork".to_string()),
+                metadata.archived.then(|| "archived".to_string()),
+                metadata.mirror.then(|| "mirror".to_string()),
+            ]
+            .into_iter()
+            .flatten()
+            .collect::<Vec<String>>();
+
+            println!(
+                "{} {} {}",
+                metadata.name.blue(),
+                repository.clone_url,
+                details.join(", ").dimmed()
+            );
+        }
+
+        print_skipped(&data.skipped_repositories);
+    }
+}
+
+/// Extraction of a target of a batch or a crawl, or its error
+fn print_report(global_config: &GlobalConfig, report: &TargetReport) {
+    match &report.data {
+        Some(Extraction::Repository(repository)) => {
+            Printer::new(global_config.clone()).print_repo(repository)
+        }
+        Some(Extraction::User(user)) => Printer::new(global_config.clone()).print_user(user),
+        Some(Extraction::Org(org)) => Printer::new(global_config.clone()).print_org(org),
+        Some(Extraction::Listing(listing)) => {
+            Printer::new(global_config.clone()).print_listing(listing)
+        }
+        None => {}
+    }
+
+    if report.status == TargetStatus::Failed {
+        let error = report.error.as_deref().unwrap_or_default();
+        println!("{}", format!("Failed : {}", error).red());
+    }
+}
+
+fn print_sample_commits(commits: &[SampleCommit]) {
+    for commit in commits {
+        let sha = commit.sha.chars().take(7).collect::<String>();
+        println!(
+            " {} {} {}",
+            sha.yellow(),
+            commit.repository.blue(),
+            commit.message.dimmed()
+        );
+    }
+}
+
+fn print_skipped(skipped_repositories: &[SkippedRepository]) {
+    for skipped in skipped_repositories {
+        let skipped_format = format!("[ Skipped : {} ({}) ]", skipped.name, skipped.reason);
+        println!("{}", skipped_format.dimmed());
+    }
+}
+
+/// Repository name with its listing status : `[ Repository : name (fork, archived) ]`
+fn print_repository_header(repo_name: &str, repository: &Repository) {
+    let status = [
+        (repository.fork, "fork"),
+        (repository.archived, "archived"),
+        (repository.mirror, "mirror"),
+    ]
+    .iter()
+    .filter(|(flag, _)| *flag)
+    .map(|(_, label)| *label)
+    .collect::<Vec<&str>>();
+
+    let repo_format = match status.is_empty() {
+        true => format!("[ Repository : {} ]", repo_name),
+        false => format!("[ Repository : {} ({}) ]", repo_name, status.join(", ")),
+    };
+    println!("{}", repo_format.magenta());
+}
+
+fn print_mail(mails: Vec<String>, author: &str, host: Option<&str>) {
     if mails.len() == 1 {
         let mail: &str = mails.first().unwrap().trim();
-        let fmail = format_mail(mail);
+        let fmail = format_mail(mail, host);
         println!(" {}", fmail)
     } else {
         let author_string_len = author.len() + 2;
         let padding = " ".repeat(author_string_len);
         let mail = mails.first().unwrap().trim();
-        let fmail: String = format_mail(mail);
+        let fmail: String = format_mail(mail, host);
 
         println!(" {}", fmail);
         for mail in mails[1..].iter() {
-            let fmail = format_mail(mail.trim());
+            let fmail = format_mail(mail.trim(), host);
             println!("{}{}", padding, fmail);
         }
     }
 }
 
-fn format_mail(mail: &str) -> String {
-    if mail.contains("noreply.github.com") {
+fn print_ownership(ownership: &Ownership) {
+    let directories = ownership
+        .top_directories(TOP_OWNERSHIP_ENTRIES)
+        .iter()
+        .map(|(directory, count)| format!("{} ({})", directory, count))
+        .collect::<Vec<String>>()
+        .join(", ");
+
+    let extensions = ownership
+        .top_extensions(TOP_OWNERSHIP_ENTRIES)
+        .iter()
+        .map(|(extension, count)| format!("{} ({})", extension, count))
+        .collect::<Vec<String>>()
+        .join(", ");
+
+    println!("   {} {}", "dirs:".dimmed(), directories);
+    println!("   {} {}", "exts:".dimmed(), extensions);
+    println!(
+        "   {} {} {}",
+        "lines:".dimmed(),
+        format!("+{}", ownership.lines_added).green(),
+        format!("-{}", ownership.lines_removed).red()
+    );
+}
+
+fn print_blame(blame: &Blame, host: Option<&str>) {
+    let blame_format = format!("[ Blame : {} lines at HEAD ]", blame.total_lines).yellow();
+    println!("{}", blame_format);
+
+    for (author, owner) in blame.ranked_owners() {
+        let share = if blame.total_lines == 0 {
+            0.0
+        } else {
+            owner.lines as f64 * 100.0 / blame.total_lines as f64
+        };
+
+        println!(
+            "{}: {} lines ({:.1}%)",
+            author.to_string().trim().blue(),
+            owner.lines,
+            share
+        );
+
+        let mails = owner.mails.keys().cloned().collect::<Vec<String>>();
+        print_mail(mails, author.to_string().trim(), host);
+    }
+}
+
+/// github.com hides mails behind `users.noreply.github.com`,
+/// a GitHub Enterprise Server behind `users.noreply.<host>`
+fn is_noreply(mail: &str, host: Option<&str>) -> bool {
+    let domain = mail.rsplit_once('@').map_or("", |(_, domain)| domain);
+
+    domain.ends_with(GITHUB_NOREPLY_DOMAIN)
+        || host.is_some_and(|host| domain == format!("users.noreply.{}", host))
+}
+
+fn format_mail(mail: &str, host: Option<&str>) -> String {
+    if is_noreply(mail, host) {
         mail.red().to_string()
     } else {
         mail.green().to_string()
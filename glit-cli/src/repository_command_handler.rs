@@ -1,28 +1,39 @@
 use clap::ArgMatches;
-use glit_core::config::RepositoryConfig;
-use reqwest::Url;
+use glit_core::{
+    config::{ForgeKind, RepositoryConfig},
+    target::{Target, TargetError},
+};
 
-use crate::utils::fix_input_url;
+use crate::utils::{log_config, target_input};
 
 pub struct RepoCommandHandler {}
 
 impl RepoCommandHandler {
     pub fn config(subcommand_match: &ArgMatches) -> RepositoryConfig {
-        let repo_url = subcommand_match
-            .get_one::<String>("repo_url")
-            .unwrap()
-            .as_str();
+        let repo_url = subcommand_match.get_one::<String>("url").unwrap();
+        Self::config_for(subcommand_match, repo_url)
+            .unwrap_or_else(|e| panic!("Invalid repository url : {}", e))
+    }
 
+    /// Config of one target, the other options are read from the subcommand
+    pub fn config_for(
+        subcommand_match: &ArgMatches,
+        repo_url: &str,
+    ) -> Result<RepositoryConfig, TargetError> {
         let all_branches = subcommand_match
             .get_one::<bool>("all_branches")
             .unwrap()
             .to_owned();
 
-        let repository_url = fix_input_url(repo_url);
+        let target = Target::repository(&target_input(subcommand_match, repo_url))?;
+
+        let forge = ForgeKind::detect(&target.clone_url);
+        let log_config = log_config(subcommand_match, forge, &target.clone_url);
 
-        RepositoryConfig {
-            url: Url::parse(&repository_url).unwrap(),
+        Ok(RepositoryConfig {
+            url: target.clone_url,
             all_branches,
-        }
+            log_config,
+        })
     }
 }
@@ -0,0 +1,42 @@
+use clap::ArgMatches;
+use glit_core::{
+    batch::BatchError,
+    forge::{forge_for, probe_target, TargetKind},
+    target::Target,
+};
+use reqwest::Client;
+
+use crate::utils::{api_config, backend, forge, listing_filter, target_input};
+
+pub struct ScanCommandHandler {}
+
+impl ScanCommandHandler {
+    /// Ask the forge of the url whether it points to a repository, a user or an organisation
+    pub async fn target_kind(
+        subcommand_match: &ArgMatches,
+        url: &str,
+        client: &Client,
+    ) -> Result<TargetKind, BatchError> {
+        let target = Target::owner(&target_input(subcommand_match, url))?;
+
+        // file:// targets have no web page to probe
+        let web_url = match target.web_url() {
+            Some(web_url) => web_url,
+            None => return Ok(TargetKind::Repository),
+        };
+
+        let forge_kind = forge(subcommand_match, &web_url);
+        let backend = backend(subcommand_match, &web_url);
+        let api_config = api_config(subcommand_match, forge_kind, &web_url);
+
+        let forge = forge_for(
+            forge_kind,
+            backend,
+            client,
+            &api_config,
+            &web_url,
+            listing_filter(subcommand_match),
+        );
+        Ok(probe_target(forge.as_ref(), &target).await?)
+    }
+}
@@ -1,28 +1,63 @@
 use clap::ArgMatches;
-use glit_core::config::UserConfig;
-use reqwest::Url;
+use glit_core::{
+    config::UserConfig,
+    target::{Target, TargetError},
+};
 
-use crate::utils::fix_input_url;
+use crate::utils::{
+    api_config, backend, forge, listing_filter, log_config, selection, target_input,
+};
 
 pub struct UserCommandHandler {}
 
 impl UserCommandHandler {
     pub fn config(subcommand_match: &ArgMatches) -> UserConfig {
-        let user_url = subcommand_match
-            .get_one::<String>("user_url")
-            .unwrap()
-            .as_str();
+        let user_url = subcommand_match.get_one::<String>("url").unwrap();
+        Self::config_for(subcommand_match, user_url)
+            .unwrap_or_else(|e| panic!("Invalid user url : {}", e))
+    }
 
+    /// Config of one target, the other options are read from the subcommand
+    pub fn config_for(
+        subcommand_match: &ArgMatches,
+        user_url: &str,
+    ) -> Result<UserConfig, TargetError> {
         let all_branches = subcommand_match
             .get_one::<bool>("all_branches")
             .unwrap()
             .to_owned();
 
-        let user_url = fix_input_url(user_url);
+        let gists = subcommand_match
+            .get_one::<bool>("gists")
+            .unwrap()
+            .to_owned();
+
+        let contributions = subcommand_match.get_flag("contributions");
+
+        // file:// targets have no user or organization page
+        let target = Target::owner(&target_input(subcommand_match, user_url))?;
+        let url = target
+            .web_url()
+            .ok_or_else(|| TargetError::UnsupportedScheme(target.scheme.clone()))?;
+
+        let forge = forge(subcommand_match, &url);
+        let log_config = log_config(subcommand_match, forge, &url);
+        let backend = backend(subcommand_match, &url);
+        let api_config = api_config(subcommand_match, forge, &url);
+        let listing_filter = listing_filter(subcommand_match);
+        let selection = selection(subcommand_match);
 
-        UserConfig {
-            url: Url::parse(&user_url).unwrap(),
+        Ok(UserConfig {
+            url,
             all_branches,
-        }
+            log_config,
+            forge,
+            backend,
+            api_config,
+            listing_filter,
+            selection,
+            gists,
+            contributions,
+        })
     }
 }
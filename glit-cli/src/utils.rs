@@ -1,3 +1,21 @@
+use clap::ArgMatches;
+use glit_core::{
+    config::{ApiConfig, Backend, CrawlConfig, ForgeKind, ListingFilter, LogConfig},
+    crawl::CrawlScope,
+    credentials::Credentials,
+    log::PathFilter,
+    selection::RepositorySelection,
+};
+use reqwest::Url;
+use std::{env, path::PathBuf};
+
+const GITHUB_HOST: &str = "github.com";
+pub(crate) const TOKEN_ENV: &str = "GITHUB_TOKEN";
+const GITLAB_TOKEN_ENV: &str = "GITLAB_TOKEN";
+const GITEA_TOKEN_ENV: &str = "GITEA_TOKEN";
+const BITBUCKET_TOKEN_ENV: &str = "BITBUCKET_TOKEN";
+const SOURCEHUT_TOKEN_ENV: &str = "SRHT_TOKEN";
+
 pub fn fix_input_url(input_url: &str) -> String {
     let mut url = String::new();
     if !&input_url.ends_with('/') {
@@ -8,3 +26,176 @@ pub fn fix_input_url(input_url: &str) -> String {
 
     input_url.to_string()
 }
+
+/// Resolve a bare owner or repository path against `--base-url`, full urls are kept as is
+pub fn target_input(subcommand_match: &ArgMatches, input: &str) -> String {
+    match subcommand_match.get_one::<String>("base_url") {
+        Some(base_url) if !input.contains(':') => Url::parse(&fix_input_url(base_url))
+            .and_then(|base_url| base_url.join(input.trim_start_matches('/')))
+            .expect("Invalid --base-url")
+            .to_string(),
+        _ => input.to_string(),
+    }
+}
+
+/// The clone token is only sent to the host of the target, see `Credentials::hosts`
+pub fn log_config(subcommand_match: &ArgMatches, forge: ForgeKind, target_url: &Url) -> LogConfig {
+    let paths = subcommand_match
+        .get_many::<String>("path")
+        .map(|paths| paths.cloned().collect::<Vec<String>>())
+        .unwrap_or_default();
+
+    let ownership = subcommand_match
+        .get_one::<bool>("ownership")
+        .unwrap()
+        .to_owned();
+
+    let blame = subcommand_match
+        .get_one::<bool>("blame")
+        .unwrap()
+        .to_owned();
+
+    let submodule_depth = subcommand_match
+        .get_one::<usize>("submodules")
+        .unwrap_or(&0)
+        .to_owned();
+
+    let wiki = subcommand_match.get_one::<bool>("wiki").unwrap().to_owned();
+
+    let max_commits = subcommand_match.get_one::<usize>("max_commits").copied();
+
+    LogConfig {
+        credentials: credentials(subcommand_match, forge, target_url),
+        path_filter: PathFilter::new(paths).expect("Invalid --path glob"),
+        ownership,
+        blame,
+        submodule_depth,
+        wiki,
+        max_commits,
+    }
+}
+
+/// Without `--backend`, github.com pages are scraped while GitHub Enterprise Server,
+/// whose page layout depends on its version, is listed with the REST API
+pub fn backend(subcommand_match: &ArgMatches, target_url: &Url) -> Backend {
+    match subcommand_match
+        .get_one::<String>("backend")
+        .map(String::as_str)
+    {
+        Some("api") => Backend::Api,
+        Some("graphql") => Backend::Graphql,
+        Some(_) => Backend::Html,
+        None if target_url.host_str() == Some(GITHUB_HOST) => Backend::Html,
+        None => Backend::Api,
+    }
+}
+
+pub fn forge(subcommand_match: &ArgMatches, target_url: &Url) -> ForgeKind {
+    match subcommand_match
+        .get_one::<String>("forge")
+        .map(String::as_str)
+    {
+        Some("github") => ForgeKind::Github,
+        Some("gitlab") => ForgeKind::Gitlab,
+        Some("gitea") => ForgeKind::Gitea,
+        Some("bitbucket") => ForgeKind::Bitbucket,
+        Some("sourcehut") => ForgeKind::Sourcehut,
+        _ => ForgeKind::detect(target_url),
+    }
+}
+
+pub fn api_config(subcommand_match: &ArgMatches, forge: ForgeKind, target_url: &Url) -> ApiConfig {
+    let base_url = subcommand_match
+        .get_one::<String>("api_url")
+        .map(|api_url| Url::parse(&fix_input_url(api_url)).unwrap());
+
+    let token = subcommand_match
+        .get_one::<String>("token")
+        .cloned()
+        .or_else(|| env::var(token_env(forge)).ok());
+
+    let mut api_config = ApiConfig::new(forge, target_url, base_url, token);
+    api_config.username = subcommand_match.get_one::<String>("api_username").cloned();
+    api_config
+}
+
+pub fn listing_filter(subcommand_match: &ArgMatches) -> ListingFilter {
+    ListingFilter {
+        include_forks: subcommand_match.get_flag("include_forks"),
+        exclude_archived: subcommand_match.get_flag("exclude_archived"),
+        only_mirrors: subcommand_match.get_flag("only_mirrors"),
+    }
+}
+
+/// `None` unless `--depth` is given, only the user subcommand crawls
+pub fn crawl_config(subcommand_match: &ArgMatches) -> Option<CrawlConfig> {
+    let depth = *subcommand_match.try_get_one::<usize>("depth").ok()??;
+    let values = |id: &str| {
+        subcommand_match
+            .get_many::<String>(id)
+            .map(|values| values.cloned().collect::<Vec<String>>())
+            .unwrap_or_default()
+    };
+
+    let scope = CrawlScope::new(&values("scope_owner"))
+        .unwrap_or_else(|e| panic!("Invalid --scope-owner pattern : {}", e));
+
+    Some(CrawlConfig {
+        depth,
+        max_targets: *subcommand_match.get_one::<usize>("max_targets").unwrap(),
+        scope,
+    })
+}
+
+pub fn selection(subcommand_match: &ArgMatches) -> RepositorySelection {
+    let patterns = |id: &str| {
+        subcommand_match
+            .get_many::<String>(id)
+            .map(|patterns| patterns.cloned().collect::<Vec<String>>())
+            .unwrap_or_default()
+    };
+
+    RepositorySelection::new(
+        &patterns("repo_include"),
+        &patterns("repo_exclude"),
+        subcommand_match.get_one::<usize>("max_repos").copied(),
+        subcommand_match.get_one::<u64>("max_repo_size").copied(),
+    )
+    .unwrap_or_else(|e| panic!("Invalid --repo-include or --repo-exclude pattern : {}", e))
+}
+
+/// Environment variable holding the token of a forge
+fn token_env(forge: ForgeKind) -> &'static str {
+    match forge {
+        ForgeKind::Github => TOKEN_ENV,
+        ForgeKind::Gitlab => GITLAB_TOKEN_ENV,
+        ForgeKind::Gitea => GITEA_TOKEN_ENV,
+        ForgeKind::Bitbucket => BITBUCKET_TOKEN_ENV,
+        ForgeKind::Sourcehut => SOURCEHUT_TOKEN_ENV,
+    }
+}
+
+/// Without `--clone-token`, the token of the forge of the target is used
+fn credentials(subcommand_match: &ArgMatches, forge: ForgeKind, target_url: &Url) -> Credentials {
+    let token = subcommand_match
+        .get_one::<String>("clone_token")
+        .cloned()
+        .or_else(|| env::var(token_env(forge)).ok());
+
+    Credentials {
+        token,
+        username: subcommand_match
+            .get_one::<String>("clone_username")
+            .cloned(),
+        ssh_key: subcommand_match
+            .get_one::<String>("ssh_key")
+            .map(PathBuf::from),
+        ssh_key_passphrase: subcommand_match
+            .get_one::<String>("ssh_passphrase")
+            .cloned(),
+        hosts: target_url
+            .host_str()
+            .map(|host| vec![host.to_lowercase()])
+            .unwrap_or_default(),
+    }
+}
@@ -0,0 +1,49 @@
+use clap::ArgMatches;
+use glit_core::config::{ApiConfig, ForgeKind, WhoisConfig};
+use reqwest::Url;
+use std::env;
+
+use crate::utils::{fix_input_url, TOKEN_ENV};
+
+const GITHUB_URL: &str = "https://github.com/";
+
+pub struct WhoisCommandHandler {}
+
+impl WhoisCommandHandler {
+    pub fn config(subcommand_match: &ArgMatches) -> WhoisConfig {
+        let email = subcommand_match
+            .get_one::<String>("email")
+            .unwrap()
+            .trim()
+            .to_string();
+
+        let web_url = subcommand_match
+            .get_one::<String>("base_url")
+            .map(|base_url| Url::parse(&fix_input_url(base_url)).expect("Invalid --base-url"))
+            .unwrap_or_else(|| Url::parse(GITHUB_URL).unwrap());
+        let api_url = subcommand_match
+            .get_one::<String>("api_url")
+            .map(|api_url| Url::parse(&fix_input_url(api_url)).expect("Invalid --api-url"));
+        let token = subcommand_match
+            .get_one::<String>("token")
+            .cloned()
+            .or_else(|| env::var(TOKEN_ENV).ok());
+        let api_config = ApiConfig::new(ForgeKind::Github, &web_url, api_url, token);
+
+        let max_commits = *subcommand_match.get_one::<usize>("max_commits").unwrap();
+        let sample_size = *subcommand_match.get_one::<usize>("samples").unwrap();
+
+        let gravatar_url = subcommand_match.get_flag("gravatar").then(|| {
+            let gravatar_url = subcommand_match.get_one::<String>("gravatar_url").unwrap();
+            Url::parse(&fix_input_url(gravatar_url)).expect("Invalid --gravatar-url")
+        });
+
+        WhoisConfig {
+            email,
+            api_config,
+            max_commits,
+            sample_size,
+            gravatar_url,
+        }
+    }
+}
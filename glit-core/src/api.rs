@@ -0,0 +1,473 @@
+use async_trait::async_trait;
+use reqwest::{
+    header::{HeaderMap, ACCEPT, AUTHORIZATION, LINK},
+    Client, RequestBuilder, Url,
+};
+use serde::{de::DeserializeOwned, Deserialize};
+use std::{collections::BTreeSet, convert::identity};
+
+use crate::{
+    config::{ApiConfig, ListingFilter},
+    contributions::Identities,
+    forge::{Forge, ForgeError, OwnerKind, RelatedAccount, Relation},
+    types::RepositoryMetadata,
+};
+
+const GITHUB_HOST: &str = "github.com";
+const GITHUB_API_URL: &str = "https://api.github.com/";
+const GITHUB_JSON: &str = "application/vnd.github+json";
+const ITEMS_PER_PAGE: usize = 100;
+
+/// Base url of the REST API serving a github web url.
+/// github.com is served by api.github.com, any other host is considered a GitHub Enterprise Server.
+pub fn api_base_url(url: &Url) -> Url {
+    match url.host_str() {
+        Some(GITHUB_HOST) | None => Url::parse(GITHUB_API_URL).unwrap(),
+        Some(host) => {
+            let mut base_url = url.clone();
+            base_url.set_path("/api/v3/");
+            base_url.set_query(None);
+            log::debug!("Using {} as API of {}", base_url, host);
+            base_url
+        }
+    }
+}
+
+/// Repository as returned by the `/users/{u}/repos` and `/orgs/{o}/repos` endpoints
+#[derive(Debug, Clone, Deserialize)]
+struct ApiRepository {
+    name: String,
+    html_url: String,
+    default_branch: Option<String>,
+    fork: bool,
+    #[serde(default)]
+    archived: bool,
+    mirror_url: Option<String>,
+    size: Option<u64>,
+    pushed_at: Option<String>,
+}
+
+/// Account as returned by the members, followers, following and organizations endpoints
+#[derive(Debug, Clone, Deserialize)]
+struct ApiAccount {
+    login: String,
+}
+
+/// Owner as returned by the `/users/{u}` endpoint, for users and organizations
+#[derive(Debug, Clone, Deserialize)]
+struct ApiOwner {
+    #[serde(rename = "type")]
+    kind: String,
+}
+
+/// Public profile as returned by the `/users/{u}` endpoint
+#[derive(Debug, Clone, Deserialize)]
+struct ApiProfile {
+    id: u64,
+    login: String,
+    html_url: String,
+    name: Option<String>,
+    email: Option<String>,
+}
+
+/// Event as returned by the `/users/{u}/events/public` endpoint
+#[derive(Debug, Clone, Deserialize)]
+struct ApiEvent {
+    #[serde(rename = "type")]
+    kind: String,
+    repo: ApiEventRepository,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+struct ApiEventRepository {
+    /// `owner/repository`
+    name: String,
+}
+
+/// Page of a `/search/*` endpoint
+#[derive(Debug, Clone, Deserialize)]
+struct ApiSearchPage<T> {
+    items: Vec<T>,
+}
+
+/// Issue or pull request as returned by the `/search/issues` endpoint
+#[derive(Debug, Clone, Deserialize)]
+struct ApiIssue {
+    /// `{api}/repos/{owner}/{repository}`
+    repository_url: String,
+}
+
+impl From<ApiRepository> for RepositoryMetadata {
+    fn from(repository: ApiRepository) -> Self {
+        Self {
+            name: repository.name,
+            url: repository.html_url,
+            default_branch: repository.default_branch,
+            fork: repository.fork,
+            archived: repository.archived,
+            mirror: repository.mirror_url.is_some(),
+            size_kb: repository.size,
+            pushed_at: repository.pushed_at,
+        }
+    }
+}
+
+pub struct GithubApi<'a> {
+    client: &'a Client,
+    config: &'a ApiConfig,
+    listing_filter: ListingFilter,
+}
+
+impl<'a> GithubApi<'a> {
+    pub fn new(client: &'a Client, config: &'a ApiConfig, listing_filter: ListingFilter) -> Self {
+        Self {
+            client,
+            config,
+            listing_filter,
+        }
+    }
+
+    pub fn endpoint(&self, path: &str) -> Url {
+        self.config.base_url.join(path).unwrap()
+    }
+
+    pub fn get(&self, url: Url) -> RequestBuilder {
+        let request = self.client.get(url).header(ACCEPT, GITHUB_JSON);
+        match &self.config.token {
+            Some(token) => request.header(AUTHORIZATION, format!("Bearer {}", token)),
+            None => request,
+        }
+    }
+
+    pub async fn paginate<T: DeserializeOwned>(&self, url: Url) -> Result<Vec<T>, reqwest::Error> {
+        paginate_links(
+            |url| self.get(url),
+            url,
+            ("per_page", ITEMS_PER_PAGE),
+            identity,
+            None,
+        )
+        .await
+    }
+
+    /// Fetch the pages of a `/search/*` endpoint until `max_items`, results are wrapped in `items`
+    pub async fn search<T: DeserializeOwned>(
+        &self,
+        url: Url,
+        max_items: Option<usize>,
+    ) -> Result<Vec<T>, reqwest::Error> {
+        paginate_links(
+            |url| self.get(url),
+            url,
+            ("per_page", ITEMS_PER_PAGE),
+            |page: ApiSearchPage<T>| page.items,
+            max_items,
+        )
+        .await
+    }
+}
+
+#[async_trait]
+impl Forge for GithubApi<'_> {
+    async fn owner_kind(&self, owner: &str) -> Result<OwnerKind, ForgeError> {
+        let url = self.endpoint(&format!("users/{}", owner));
+        let owner = self
+            .get(url)
+            .send()
+            .await?
+            .error_for_status()?
+            .json::<ApiOwner>()
+            .await?;
+
+        match owner.kind.as_str() {
+            "Organization" => Ok(OwnerKind::Org),
+            _ => Ok(OwnerKind::User),
+        }
+    }
+
+    async fn list_repositories(
+        &self,
+        owner_kind: OwnerKind,
+        owner: &str,
+    ) -> Result<Vec<RepositoryMetadata>, ForgeError> {
+        let mut url = self.endpoint(&format!("{}/{}/repos", owner_kind.path_segment(), owner));
+        // Only organizations can filter forks out, user repositories are filtered afterwards
+        if owner_kind == OwnerKind::Org && !self.listing_filter.include_forks {
+            url.query_pairs_mut().append_pair("type", "sources");
+        }
+
+        let repositories = self
+            .paginate::<ApiRepository>(url)
+            .await?
+            .into_iter()
+            .map(RepositoryMetadata::from)
+            .collect::<Vec<_>>();
+
+        log::info!(
+            "API listed {} repositories for {}",
+            repositories.len(),
+            owner
+        );
+        Ok(repositories)
+    }
+
+    async fn list_members(&self, org: &str) -> Result<Vec<String>, ForgeError> {
+        let url = self.endpoint(&format!("orgs/{}/public_members", org));
+        let members = self.paginate::<ApiAccount>(url).await?;
+
+        Ok(members.into_iter().map(|member| member.login).collect())
+    }
+
+    async fn list_related_accounts(&self, user: &str) -> Result<Vec<RelatedAccount>, ForgeError> {
+        let mut related_accounts = Vec::new();
+
+        for (relation, path) in [
+            (Relation::Follower, "followers"),
+            (Relation::Following, "following"),
+            (Relation::Organization, "orgs"),
+        ] {
+            let url = self.endpoint(&format!("users/{}/{}", user, path));
+            let accounts = self.paginate::<ApiAccount>(url).await?;
+
+            related_accounts.extend(accounts.into_iter().map(|account| RelatedAccount {
+                login: account.login,
+                relation,
+            }));
+        }
+
+        Ok(related_accounts)
+    }
+
+    /// Repositories of recent public push events and of merged pull requests.
+    /// Events only cover the last 90 days, the search covers the first 1000 pull requests.
+    async fn list_contributions(&self, user: &str) -> Result<Vec<RepositoryMetadata>, ForgeError> {
+        let mut full_names = BTreeSet::new();
+
+        let events_url = self.endpoint(&format!("users/{}/events/public", user));
+        let events = self.paginate::<ApiEvent>(events_url).await?;
+        full_names.extend(
+            events
+                .into_iter()
+                .filter(|event| event.kind == "PushEvent")
+                .map(|event| event.repo.name),
+        );
+
+        let mut search_url = self.endpoint("search/issues");
+        search_url
+            .query_pairs_mut()
+            .append_pair("q", &format!("author:{} type:pr is:merged", user));
+        let pull_requests = self.search::<ApiIssue>(search_url, None).await?;
+        full_names.extend(pull_requests.into_iter().filter_map(|pull_request| {
+            let mut segments = pull_request.repository_url.rsplit('/');
+            let repository = segments.next()?;
+            let owner = segments.next()?;
+            Some(format!("{}/{}", owner, repository))
+        }));
+
+        let mut repositories = Vec::new();
+        for full_name in full_names {
+            let owned = full_name
+                .split_once('/')
+                .is_some_and(|(owner, _)| owner.eq_ignore_ascii_case(user));
+            if owned {
+                continue;
+            }
+
+            let url = self.endpoint(&format!("repos/{}", full_name));
+            let repository = match self
+                .get(url)
+                .send()
+                .await
+                .and_then(|resp| resp.error_for_status())
+            {
+                Ok(resp) => resp.json::<ApiRepository>().await?,
+                Err(e) => {
+                    log::warn!("Skip contribution to {} : {}", full_name, e);
+                    continue;
+                }
+            };
+            repositories.push(RepositoryMetadata::from(repository));
+        }
+
+        log::info!(
+            "API found {} repositories {} contributed to",
+            repositories.len(),
+            user
+        );
+        Ok(repositories)
+    }
+
+    /// Login, profile name and email, and the noreply addresses used by web edits
+    async fn known_identities(&self, user: &str) -> Result<Identities, ForgeError> {
+        let url = self.endpoint(&format!("users/{}", user));
+        let profile = self
+            .get(url)
+            .send()
+            .await?
+            .error_for_status()?
+            .json::<ApiProfile>()
+            .await?;
+
+        let mut identities = Identities::new(&profile.login);
+        if let Some(name) = &profile.name {
+            identities.add_name(name);
+        }
+        if let Some(email) = &profile.email {
+            identities.add_email(email);
+        }
+        let profile_url = Url::parse(&profile.html_url).ok();
+        if let Some(host) = profile_url.as_ref().and_then(Url::host_str) {
+            identities.add_email(&format!("{}@users.noreply.{}", profile.login, host));
+            identities.add_email(&format!(
+                "{}+{}@users.noreply.{}",
+                profile.id, profile.login, host
+            ));
+        }
+
+        Ok(identities)
+    }
+}
+
+pub(crate) fn next_link(headers: &HeaderMap) -> Option<Url> {
+    let link = headers.get(LINK)?.to_str().ok()?;
+
+    link.split(',').find_map(|part| {
+        let (url, rel) = part.split_once(';')?;
+        if rel.trim() != r#"rel="next""# {
+            return None;
+        }
+
+        let url = url.trim().trim_start_matches('<').trim_end_matches('>');
+        Url::parse(url).ok()
+    })
+}
+
+/// Fetch every page of a list endpoint by following the `Link: <...>; rel="next"` header.
+/// `request` builds the authenticated request of a page, `page_size` is the query parameter setting
+/// the page size and its value, next links already carry it. `items` extracts the items of a page,
+/// `identity` for endpoints answering a bare array, and no page is fetched past `max_items`.
+pub(crate) async fn paginate_links<P: DeserializeOwned, T>(
+    request: impl Fn(Url) -> RequestBuilder,
+    url: Url,
+    page_size: (&str, usize),
+    items: impl Fn(P) -> Vec<T>,
+    max_items: Option<usize>,
+) -> Result<Vec<T>, reqwest::Error> {
+    let (page_size_param, page_size) = page_size;
+    let max_items = max_items.unwrap_or(usize::MAX);
+    let mut url = url;
+    url.query_pairs_mut()
+        .append_pair(page_size_param, &page_size.to_string());
+
+    let mut all_items = Vec::new();
+    let mut next_url = Some(url);
+
+    while let Some(url) = next_url {
+        let resp = request(url).send().await?.error_for_status()?;
+
+        next_url = next_link(resp.headers());
+        all_items.extend(items(resp.json::<P>().await?));
+
+        if all_items.len() >= max_items {
+            all_items.truncate(max_items);
+            break;
+        }
+    }
+
+    Ok(all_items)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        config::ForgeKind,
+        mock::{self, MockPage},
+    };
+    use reqwest::header::HeaderValue;
+
+    fn link_headers(link: &str) -> HeaderMap {
+        let mut headers = HeaderMap::new();
+        headers.insert(LINK, HeaderValue::from_str(link).unwrap());
+        headers
+    }
+
+    #[test]
+    fn next_link_picks_the_next_relation() {
+        let headers = link_headers(
+            r#"<https://api.github.com/orgs/acme/repos?page=1>; rel="prev", <https://api.github.com/orgs/acme/repos?page=3>; rel="next", <https://api.github.com/orgs/acme/repos?page=5>; rel="last""#,
+        );
+
+        assert_eq!(
+            next_link(&headers).unwrap().as_str(),
+            "https://api.github.com/orgs/acme/repos?page=3"
+        );
+    }
+
+    #[test]
+    fn next_link_is_none_on_the_last_page() {
+        let headers = link_headers(
+            r#"<https://api.github.com/orgs/acme/repos?page=1>; rel="first", <https://api.github.com/orgs/acme/repos?page=4>; rel="prev""#,
+        );
+
+        assert_eq!(next_link(&headers), None);
+        assert_eq!(next_link(&HeaderMap::new()), None);
+    }
+
+    #[test]
+    fn api_base_url_of_enterprise_server() {
+        let url = Url::parse("https://ghe.example.com/security/").unwrap();
+
+        assert_eq!(
+            api_base_url(&url).as_str(),
+            "https://ghe.example.com/api/v3/"
+        );
+        assert_eq!(
+            api_base_url(&Url::parse("https://github.com/netflix").unwrap()).as_str(),
+            GITHUB_API_URL
+        );
+    }
+
+    #[tokio::test]
+    async fn paginate_follows_link_headers_of_the_api_url() {
+        let (base_url, server) = mock::serve(vec![
+            MockPage::new(
+                r#"[{"name":"one","html_url":"https://ghe.example.com/acme/one","fork":false,"size":12}]"#,
+                Some("api/v3/orgs/acme/repos?type=sources&per_page=100&page=2"),
+            ),
+            MockPage::new(
+                r#"[{"name":"two","html_url":"https://ghe.example.com/acme/two","fork":true,"archived":true}]"#,
+                None,
+            ),
+        ]);
+        let api_url = base_url.join("api/v3/").unwrap();
+
+        let client = Client::new();
+        let target_url = Url::parse("https://ghe.example.com/acme/").unwrap();
+        let config = ApiConfig::new(
+            ForgeKind::Github,
+            &target_url,
+            Some(api_url),
+            Some("secret".to_string()),
+        );
+        let api = GithubApi::new(&client, &config, ListingFilter::default());
+
+        let repositories = api.list_repositories(OwnerKind::Org, "acme").await.unwrap();
+        let requests = server.join().unwrap();
+
+        let names = repositories
+            .iter()
+            .map(|repository| repository.name.as_str())
+            .collect::<Vec<&str>>();
+        assert_eq!(names, ["one", "two"]);
+        assert_eq!(repositories[0].size_kb, Some(12));
+        assert!(repositories[1].fork && repositories[1].archived);
+
+        assert!(requests[0].starts_with("GET /api/v3/orgs/acme/repos?type=sources&per_page=100 "));
+        assert!(requests[1]
+            .starts_with("GET /api/v3/orgs/acme/repos?type=sources&per_page=100&page=2 "));
+        assert!(requests
+            .iter()
+            .all(|request| request.contains("authorization: Bearer secret")));
+    }
+}
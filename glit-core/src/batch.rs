@@ -0,0 +1,165 @@
+use serde::Serialize;
+use std::{collections::BTreeMap, fmt};
+
+use crate::{
+    forge::{ForgeError, TargetKind},
+    listing::Listing,
+    org::Org,
+    repo::Repository,
+    target::TargetError,
+    user::User,
+};
+
+/// Why one target of a list could not be scanned
+#[derive(Debug)]
+pub enum BatchError {
+    Target(TargetError),
+    Forge(ForgeError),
+    Clone(git2::Error),
+}
+
+impl fmt::Display for BatchError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            BatchError::Target(e) => write!(f, "invalid url : {}", e),
+            BatchError::Forge(e) => write!(f, "{}", e),
+            BatchError::Clone(e) => write!(f, "failed to clone : {}", e.message()),
+        }
+    }
+}
+
+impl From<TargetError> for BatchError {
+    fn from(e: TargetError) -> Self {
+        BatchError::Target(e)
+    }
+}
+
+impl From<ForgeError> for BatchError {
+    fn from(e: ForgeError) -> Self {
+        BatchError::Forge(e)
+    }
+}
+
+impl From<git2::Error> for BatchError {
+    fn from(e: git2::Error) -> Self {
+        BatchError::Clone(e)
+    }
+}
+
+/// Data extracted from one target
+#[derive(Serialize)]
+#[serde(untagged)]
+pub enum Extraction {
+    Repository(Repository),
+    User(User),
+    Org(Box<Org>),
+    /// Dry run, nothing was cloned
+    Listing(Listing),
+}
+
+impl Extraction {
+    pub fn kind(&self) -> TargetKind {
+        match self {
+            Extraction::Repository(_) => TargetKind::Repository,
+            Extraction::User(_) => TargetKind::User,
+            Extraction::Org(_) => TargetKind::Org,
+            Extraction::Listing(listing) => listing.kind,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum TargetStatus {
+    Done,
+    Failed,
+}
+
+#[derive(Serialize)]
+pub struct TargetReport {
+    pub status: TargetStatus,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub kind: Option<TargetKind>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub data: Option<Extraction>,
+}
+
+impl TargetReport {
+    pub fn done(extraction: Extraction) -> Self {
+        Self {
+            status: TargetStatus::Done,
+            kind: Some(extraction.kind()),
+            error: None,
+            data: Some(extraction),
+        }
+    }
+
+    pub fn failed(error: String) -> Self {
+        Self {
+            status: TargetStatus::Failed,
+            kind: None,
+            error: Some(error),
+            data: None,
+        }
+    }
+}
+
+/// Reports of a list of targets, keyed by the target as written in the list.
+/// A failed target is reported with its error and does not stop the others.
+#[derive(Default, Serialize)]
+pub struct Batch {
+    pub targets: BTreeMap<String, TargetReport>,
+}
+
+impl Batch {
+    /// Targets of a newline separated list, blank lines and `#` comments are ignored
+    pub fn parse_targets(list: &str) -> Vec<String> {
+        list.lines()
+            .map(str::trim)
+            .filter(|line| !line.is_empty() && !line.starts_with('#'))
+            .map(str::to_string)
+            .collect()
+    }
+
+    pub fn insert(&mut self, target: String, report: TargetReport) {
+        self.targets.insert(target, report);
+    }
+
+    pub fn failed_count(&self) -> usize {
+        self.targets
+            .values()
+            .filter(|report| report.status == TargetStatus::Failed)
+            .count()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_targets_skips_blank_lines_and_comments() {
+        let list = "# repositories\nhttps://github.com/rust-lang/git2-rs\n\n   \n  https://github.com/netflix  \r\n#https://github.com/skipped\ngit@github.com:acme/app.git";
+
+        assert_eq!(
+            Batch::parse_targets(list),
+            [
+                "https://github.com/rust-lang/git2-rs",
+                "https://github.com/netflix",
+                "git@github.com:acme/app.git",
+            ]
+        );
+        assert!(Batch::parse_targets("\n# nothing\n").is_empty());
+    }
+
+    #[test]
+    fn failed_count() {
+        let mut batch = Batch::default();
+        batch.insert("a".to_string(), TargetReport::failed("boom".to_string()));
+        batch.insert("b".to_string(), TargetReport::failed("boom".to_string()));
+
+        assert_eq!(batch.failed_count(), 2);
+    }
+}
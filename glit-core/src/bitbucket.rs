@@ -0,0 +1,259 @@
+use async_trait::async_trait;
+use reqwest::{Client, RequestBuilder, Url};
+use serde::{de::DeserializeOwned, Deserialize};
+
+use crate::{
+    config::ApiConfig,
+    forge::{Forge, ForgeError, OwnerKind, TargetKind},
+    types::RepositoryMetadata,
+};
+
+const BITBUCKET_CLOUD_HOST: &str = "bitbucket.org";
+const BITBUCKET_CLOUD_API_URL: &str = "https://api.bitbucket.org/2.0/";
+const BITBUCKET_SERVER_API_PATH: &str = "/rest/api/1.0/";
+const ITEMS_PER_PAGE: usize = 100;
+
+/// Base url of the REST API serving a bitbucket web url.
+/// bitbucket.org is served by api.bitbucket.org, any other host is considered a Bitbucket Server.
+pub fn api_base_url(url: &Url) -> Url {
+    match url.host_str() {
+        Some(BITBUCKET_CLOUD_HOST) | None => Url::parse(BITBUCKET_CLOUD_API_URL).unwrap(),
+        Some(_) => {
+            let mut base_url = url.clone();
+            base_url.set_path(BITBUCKET_SERVER_API_PATH);
+            base_url.set_query(None);
+            base_url
+        }
+    }
+}
+
+#[derive(Debug, Clone, Deserialize)]
+struct Link {
+    name: Option<String>,
+    href: String,
+}
+
+/// Page of the Bitbucket Cloud `/repositories/{workspace}` endpoint
+#[derive(Debug, Deserialize)]
+struct CloudPage {
+    values: Vec<CloudRepository>,
+    next: Option<String>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+struct CloudRepository {
+    slug: String,
+    links: CloudLinks,
+    mainbranch: Option<Branch>,
+    parent: Option<serde::de::IgnoredAny>,
+    size: Option<u64>,
+    updated_on: Option<String>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+struct CloudLinks {
+    clone: Vec<Link>,
+    html: Link,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+struct Branch {
+    name: String,
+}
+
+impl From<CloudRepository> for RepositoryMetadata {
+    fn from(repository: CloudRepository) -> Self {
+        Self {
+            url: https_clone_link(&repository.links.clone).unwrap_or(repository.links.html.href),
+            name: repository.slug,
+            default_branch: repository.mainbranch.map(|branch| branch.name),
+            fork: repository.parent.is_some(),
+            archived: false,
+            mirror: false,
+            size_kb: repository.size.map(|size| size / 1024),
+            pushed_at: repository.updated_on,
+        }
+    }
+}
+
+/// Page of the Bitbucket Server `/projects/{key}/repos` and `/users/{slug}/repos` endpoints
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct ServerPage {
+    values: Vec<ServerRepository>,
+    is_last_page: bool,
+    next_page_start: Option<usize>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+struct ServerRepository {
+    slug: String,
+    links: ServerLinks,
+    origin: Option<serde::de::IgnoredAny>,
+    #[serde(default)]
+    archived: bool,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+struct ServerLinks {
+    clone: Vec<Link>,
+}
+
+impl From<ServerRepository> for RepositoryMetadata {
+    fn from(repository: ServerRepository) -> Self {
+        Self {
+            url: https_clone_link(&repository.links.clone).unwrap_or_default(),
+            name: repository.slug,
+            default_branch: None,
+            fork: repository.origin.is_some(),
+            archived: repository.archived,
+            mirror: false,
+            size_kb: None,
+            pushed_at: None,
+        }
+    }
+}
+
+/// Bitbucket Cloud names the https clone link `https`, Bitbucket Server names it `http`
+fn https_clone_link(links: &[Link]) -> Option<String> {
+    links
+        .iter()
+        .find(|link| matches!(link.name.as_deref(), Some("https") | Some("http")))
+        .map(|link| link.href.clone())
+}
+
+pub struct BitbucketApi<'a> {
+    client: &'a Client,
+    config: &'a ApiConfig,
+}
+
+impl<'a> BitbucketApi<'a> {
+    pub fn new(client: &'a Client, config: &'a ApiConfig) -> Self {
+        Self { client, config }
+    }
+
+    /// App passwords are sent with basic auth along the username, tokens as bearer
+    fn get(&self, url: Url) -> RequestBuilder {
+        let request = self.client.get(url);
+        match (&self.config.username, &self.config.token) {
+            (Some(username), token) => request.basic_auth(username, token.as_ref()),
+            (None, Some(token)) => request.bearer_auth(token),
+            (None, None) => request,
+        }
+    }
+
+    async fn get_json<T: DeserializeOwned>(&self, url: Url) -> Result<T, reqwest::Error> {
+        self.get(url)
+            .send()
+            .await?
+            .error_for_status()?
+            .json::<T>()
+            .await
+    }
+
+    /// List repositories of a Bitbucket Cloud workspace
+    async fn list_workspace_repositories(
+        &self,
+        workspace: &str,
+    ) -> Result<Vec<RepositoryMetadata>, reqwest::Error> {
+        let mut url = self.endpoint(&format!("repositories/{}", workspace));
+        url.query_pairs_mut()
+            .append_pair("pagelen", &ITEMS_PER_PAGE.to_string());
+
+        let mut repositories = Vec::new();
+        let mut next_url = Some(url);
+
+        while let Some(url) = next_url {
+            let page = self.get_json::<CloudPage>(url).await?;
+            next_url = page.next.and_then(|next| Url::parse(&next).ok());
+            repositories.extend(page.values.into_iter().map(RepositoryMetadata::from));
+        }
+
+        Ok(Self::with_clone_links(repositories, workspace))
+    }
+
+    /// List repositories of a Bitbucket Server owner path,
+    /// `projects/{key}` for a project or `users/{slug}` for a personal project
+    async fn list_server_repositories(
+        &self,
+        owner: &str,
+    ) -> Result<Vec<RepositoryMetadata>, reqwest::Error> {
+        let url = self.endpoint(&format!("{}/repos", owner));
+
+        let mut repositories = Vec::new();
+        let mut start = Some(0);
+
+        while let Some(page_start) = start {
+            let mut url = url.clone();
+            url.query_pairs_mut()
+                .append_pair("limit", &ITEMS_PER_PAGE.to_string())
+                .append_pair("start", &page_start.to_string());
+
+            let page = self.get_json::<ServerPage>(url).await?;
+            start = match page.is_last_page {
+                true => None,
+                false => page.next_page_start,
+            };
+            repositories.extend(page.values.into_iter().map(RepositoryMetadata::from));
+        }
+
+        Ok(Self::with_clone_links(repositories, owner))
+    }
+
+    /// Drop repositories without an https clone link
+    fn with_clone_links(
+        mut repositories: Vec<RepositoryMetadata>,
+        owner: &str,
+    ) -> Vec<RepositoryMetadata> {
+        repositories.retain(|repository| !repository.url.is_empty());
+
+        log::info!(
+            "Bitbucket API listed {} repositories for {}",
+            repositories.len(),
+            owner
+        );
+        repositories
+    }
+
+    fn endpoint(&self, path: &str) -> Url {
+        self.config.base_url.join(path).unwrap()
+    }
+}
+
+/// Bitbucket Server owners are paths (`projects/{key}`, `users/{slug}`), Bitbucket Cloud workspaces are not
+fn is_server_owner(owner: &str) -> bool {
+    owner.contains('/')
+}
+
+#[async_trait]
+impl Forge for BitbucketApi<'_> {
+    async fn owner_kind(&self, owner: &str) -> Result<OwnerKind, ForgeError> {
+        match owner.starts_with("users/") {
+            true => Ok(OwnerKind::User),
+            false => Ok(OwnerKind::Org),
+        }
+    }
+
+    /// Bitbucket Server owners are `projects/{key}` or `users/{slug}`, deeper paths are repositories
+    async fn target_kind(&self, path: &str) -> Result<TargetKind, ForgeError> {
+        let segments = path.split('/').collect::<Vec<&str>>();
+        match segments.as_slice() {
+            [_] | ["projects" | "users", _] => Ok(self.owner_kind(path).await?.into()),
+            _ => Ok(TargetKind::Repository),
+        }
+    }
+
+    /// List repositories of a Bitbucket Cloud workspace (`bitbucket.org/{workspace}`)
+    /// or of a Bitbucket Server project (`<host>/projects/{key}`)
+    async fn list_repositories(
+        &self,
+        _owner_kind: OwnerKind,
+        owner: &str,
+    ) -> Result<Vec<RepositoryMetadata>, ForgeError> {
+        let repositories = match is_server_owner(owner) {
+            true => self.list_server_repositories(owner).await?,
+            false => self.list_workspace_repositories(owner).await?,
+        };
+        Ok(repositories)
+    }
+}
@@ -0,0 +1,146 @@
+use crate::{log::PathFilter, types::AuthorName};
+use ahash::HashMap;
+use git2::{ObjectType, TreeWalkMode, TreeWalkResult};
+use indicatif::{ProgressBar, ProgressStyle};
+use rayon::prelude::{IntoParallelIterator, ParallelIterator};
+use serde::{Deserialize, Serialize};
+use std::{
+    cmp::Reverse,
+    collections::BTreeMap,
+    path::{Path, PathBuf},
+};
+
+type Mail = String;
+
+/// Lines of the default branch HEAD attributed to an author, split by mail.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LineOwner {
+    pub lines: usize,
+    pub mails: BTreeMap<Mail, usize>,
+}
+
+/// Current code ownership of a repository, computed by blaming every file at HEAD.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Blame {
+    pub total_lines: usize,
+    pub owners: HashMap<AuthorName, LineOwner>,
+}
+
+impl Blame {
+    pub fn build(path: &Path, repo_name: String, path_filter: &PathFilter) -> Blame {
+        let repo = git2::Repository::open_bare(path).unwrap();
+        let files = Self::head_files(&repo, path_filter);
+
+        log::info!("[{}] Blaming {} files at HEAD", repo_name, files.len());
+
+        let pb = ProgressBar::new(files.len().try_into().unwrap());
+        pb.set_message(format!("[{}][blame]", repo_name));
+        let style = ProgressStyle::with_template(
+            "🔎 BLAMING    {msg}{spinner:.green}[{elapsed_precise}] [{wide_bar:.cyan/blue}] {human_pos}/{human_len} ",
+        )
+        .unwrap()
+        .progress_chars("#>-");
+        pb.set_style(style);
+
+        // git2::Repository is not Sync, so each rayon worker opens its own handle
+        let file_blames = files
+            .into_par_iter()
+            .map_init(
+                || git2::Repository::open_bare(path).unwrap(),
+                |repo, file| {
+                    let blame = Self::blame_file(repo, &file);
+                    pb.inc(1);
+                    blame
+                },
+            )
+            .collect::<Vec<Blame>>();
+
+        pb.finish_with_message(format!("[{} ✅][blame ✅]", repo_name));
+        pb.finish_and_clear();
+
+        file_blames
+            .into_iter()
+            .fold(Blame::default(), |mut blame, file_blame| {
+                blame.merge(file_blame);
+                blame
+            })
+    }
+
+    fn head_files(repo: &git2::Repository, path_filter: &PathFilter) -> Vec<PathBuf> {
+        let tree = match repo.head().and_then(|head| head.peel_to_tree()) {
+            Ok(tree) => tree,
+            Err(e) => {
+                log::error!("Failed to resolve HEAD tree : {:?}", e);
+                return Vec::new();
+            }
+        };
+
+        let mut files = Vec::new();
+        tree.walk(TreeWalkMode::PreOrder, |root, entry| {
+            if entry.kind() != Some(ObjectType::Blob) {
+                return TreeWalkResult::Ok;
+            }
+
+            let file = PathBuf::from(format!("{}{}", root, entry.name().unwrap_or("")));
+            let is_binary = entry
+                .to_object(repo)
+                .ok()
+                .and_then(|object| object.into_blob().ok())
+                .map(|blob| blob.is_binary())
+                .unwrap_or(true);
+
+            if !is_binary && path_filter.matches(&file) {
+                files.push(file);
+            }
+
+            TreeWalkResult::Ok
+        })
+        .unwrap();
+
+        files
+    }
+
+    fn blame_file(repo: &git2::Repository, file: &Path) -> Blame {
+        let mut blame = Blame::default();
+
+        let hunks = match repo.blame_file(file, None) {
+            Ok(hunks) => hunks,
+            Err(e) => {
+                log::error!("Failed to blame {:?} : {:?}", file, e);
+                return blame;
+            }
+        };
+
+        for hunk in hunks.iter() {
+            let signature = hunk.final_signature();
+            let author = AuthorName(signature.name().unwrap_or("").to_string());
+            let mail = signature.email().unwrap_or("").to_string();
+            let lines = hunk.lines_in_hunk();
+
+            let owner = blame.owners.entry(author).or_default();
+            owner.lines += lines;
+            *owner.mails.entry(mail).or_insert(0) += lines;
+            blame.total_lines += lines;
+        }
+
+        blame
+    }
+
+    fn merge(&mut self, other: Blame) {
+        self.total_lines += other.total_lines;
+        for (author, other_owner) in other.owners {
+            let owner = self.owners.entry(author).or_default();
+            owner.lines += other_owner.lines;
+            for (mail, lines) in other_owner.mails {
+                *owner.mails.entry(mail).or_insert(0) += lines;
+            }
+        }
+    }
+
+    /// Owners sorted by number of lines, biggest first
+    pub fn ranked_owners(&self) -> Vec<(&AuthorName, &LineOwner)> {
+        let mut owners = self.owners.iter().collect::<Vec<_>>();
+        owners.sort_by_key(|(_, owner)| Reverse(owner.lines));
+        owners
+    }
+}
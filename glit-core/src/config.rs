@@ -1,20 +1,135 @@
+use crate::{
+    api, bitbucket, crawl::CrawlScope, credentials::Credentials, gitea, gitlab, log::PathFilter,
+    selection::RepositorySelection, sourcehut, types::RepositoryMetadata,
+};
 use reqwest::Url;
+use std::path::PathBuf;
 
 #[derive(Debug, Clone)]
 pub struct GlobalConfig {
     pub thread_num: usize,
     pub output: String,
+    /// Extra CA certificate (PEM) trusted for API requests and clones, like the one of a GitHub Enterprise Server
+    pub ca_cert: Option<PathBuf>,
+}
+
+/// Options applied to each repository, from cloning to data extraction
+#[derive(Debug, Clone, Default)]
+pub struct LogConfig {
+    pub credentials: Credentials,
+    pub path_filter: PathFilter,
+    pub ownership: bool,
+    pub blame: bool,
+    /// How many levels of git submodules to follow. 0 disables submodule scanning.
+    pub submodule_depth: usize,
+    pub wiki: bool,
+    /// Only walk the most recent commits of each repository, whatever branch they are on
+    pub max_commits: Option<usize>,
 }
 
 #[derive(Debug, Clone)]
 pub struct RepositoryConfig {
     pub url: Url,
     pub all_branches: bool,
+    pub log_config: LogConfig,
 }
 
 impl RepositoryConfig {
-    pub fn new(url: Url, all_branches: bool) -> Self {
-        Self { url, all_branches }
+    pub fn new(url: Url, all_branches: bool, log_config: LogConfig) -> Self {
+        Self {
+            url,
+            all_branches,
+            log_config,
+        }
+    }
+}
+
+/// How repositories of a user or an organization are listed
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Backend {
+    /// Scrape the github repositories pages
+    #[default]
+    Html,
+    /// Use the github REST API
+    Api,
+    /// Use the github GraphQL API, a token is required
+    Graphql,
+}
+
+const GITEA_HOSTS: [&str; 3] = ["gitea", "forgejo", "codeberg"];
+
+/// Code hosting platform serving a user or an organization
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ForgeKind {
+    #[default]
+    Github,
+    /// gitlab.com or a self-managed instance. Organizations are groups, with their nested subgroups.
+    Gitlab,
+    /// Gitea or Forgejo instance, like codeberg.org
+    Gitea,
+    /// Bitbucket Cloud workspace or Bitbucket Server project
+    Bitbucket,
+    /// git.sr.ht, owners are `~user`
+    Sourcehut,
+}
+
+impl ForgeKind {
+    /// Guess the forge from the host of the target url, github by default
+    pub fn detect(url: &Url) -> Self {
+        match url.host_str() {
+            Some(host) if host.contains("gitlab") => ForgeKind::Gitlab,
+            Some(host) if host.contains("bitbucket") => ForgeKind::Bitbucket,
+            Some(host) if host.ends_with("sr.ht") => ForgeKind::Sourcehut,
+            Some(host) if GITEA_HOSTS.iter().any(|name| host.contains(name)) => ForgeKind::Gitea,
+            _ => ForgeKind::Github,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct ApiConfig {
+    pub base_url: Url,
+    pub token: Option<String>,
+    /// Username of a Bitbucket app password, sent with the token as basic auth
+    pub username: Option<String>,
+}
+
+impl ApiConfig {
+    /// Without an explicit base url, the API is deduced from the forge and the host of the target url
+    pub fn new(
+        forge: ForgeKind,
+        target_url: &Url,
+        base_url: Option<Url>,
+        token: Option<String>,
+    ) -> Self {
+        let base_url = base_url.unwrap_or_else(|| match forge {
+            ForgeKind::Github => api::api_base_url(target_url),
+            ForgeKind::Gitlab => gitlab::api_base_url(target_url),
+            ForgeKind::Gitea => gitea::api_base_url(target_url),
+            ForgeKind::Bitbucket => bitbucket::api_base_url(target_url),
+            ForgeKind::Sourcehut => sourcehut::api_base_url(target_url),
+        });
+        Self {
+            base_url,
+            token,
+            username: None,
+        }
+    }
+}
+
+/// Which listed repositories are scanned. By default forks are skipped, archived repositories and mirrors are kept.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ListingFilter {
+    pub include_forks: bool,
+    pub exclude_archived: bool,
+    pub only_mirrors: bool,
+}
+
+impl ListingFilter {
+    pub fn keeps(&self, repository: &RepositoryMetadata) -> bool {
+        (self.include_forks || !repository.fork)
+            && !(self.exclude_archived && repository.archived)
+            && (!self.only_mirrors || repository.mirror)
     }
 }
 
@@ -22,10 +137,52 @@ impl RepositoryConfig {
 pub struct UserConfig {
     pub url: Url,
     pub all_branches: bool,
+    pub log_config: LogConfig,
+    pub gists: bool,
+    pub forge: ForgeKind,
+    pub backend: Backend,
+    pub api_config: ApiConfig,
+    pub listing_filter: ListingFilter,
+    pub selection: RepositorySelection,
+    /// Also scan the repositories of other owners the user contributed to
+    pub contributions: bool,
+}
+
+/// Reverse lookup of the accounts committing with an email
+#[derive(Debug, Clone)]
+pub struct WhoisConfig {
+    pub email: String,
+    pub api_config: ApiConfig,
+    /// Commits fetched from the search, which stops at 1000
+    pub max_commits: usize,
+    /// Commits reported for each account
+    pub sample_size: usize,
+    /// Base url of the gravatar profiles, `None` skips the gravatar lookup
+    pub gravatar_url: Option<Url>,
+}
+
+/// Bounds of a crawl through the followers, following and organizations of a user
+#[derive(Debug, Clone)]
+pub struct CrawlConfig {
+    /// Number of hops from the starting user, `0` only scans the starting user
+    pub depth: usize,
+    /// Number of targets scanned, starting user included
+    pub max_targets: usize,
+    pub scope: CrawlScope,
 }
 
 #[derive(Debug, Clone)]
 pub struct OrgConfig {
     pub url: Url,
     pub all_branches: bool,
+    pub log_config: LogConfig,
+    pub forge: ForgeKind,
+    pub backend: Backend,
+    pub api_config: ApiConfig,
+    pub listing_filter: ListingFilter,
+    pub selection: RepositorySelection,
+    /// List the public members of the organization
+    pub members: bool,
+    /// Also scan the repositories of each member, implies `members`
+    pub expand_members: bool,
 }
@@ -0,0 +1,92 @@
+use serde::Serialize;
+use std::collections::BTreeSet;
+
+use crate::{
+    repo::{Committer, Repository},
+    types::AuthorName,
+};
+
+/// Names and emails a user is known to commit with, compared case insensitively
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct Identities {
+    pub names: BTreeSet<String>,
+    pub emails: BTreeSet<String>,
+}
+
+impl Identities {
+    /// Only the login is known
+    pub fn new(login: &str) -> Self {
+        let mut identities = Self::default();
+        identities.add_name(login);
+        identities
+    }
+
+    pub fn add_name(&mut self, name: &str) {
+        if !name.trim().is_empty() {
+            self.names.insert(name.trim().to_lowercase());
+        }
+    }
+
+    pub fn add_email(&mut self, email: &str) {
+        if !email.trim().is_empty() {
+            self.emails.insert(email.trim().to_lowercase());
+        }
+    }
+
+    pub fn matches(&self, author: &AuthorName, committer: &Committer) -> bool {
+        self.names.contains(&author.0.to_lowercase())
+            || committer
+                .mails
+                .keys()
+                .any(|mail| self.emails.contains(&mail.to_lowercase()))
+    }
+
+    /// Adopt the names and emails of the committers of the user's own repositories that match a known identity.
+    /// A single pass, so that a collaborator sharing one name does not pull in everyone it ever committed with.
+    pub fn learn_from<'a>(&mut self, repositories: impl Iterator<Item = &'a Repository>) {
+        let mut learned = Identities::default();
+
+        for repository in repositories {
+            for committers in repository.branch_data.values() {
+                for (author, committer) in &committers.committers {
+                    if self.matches(author, committer) {
+                        learned.add_name(&author.0);
+                        committer
+                            .mails
+                            .keys()
+                            .for_each(|mail| learned.add_email(mail));
+                    }
+                }
+            }
+        }
+
+        self.names.extend(learned.names);
+        self.emails.extend(learned.emails);
+    }
+
+    /// Drop the committers and blamed authors of a repository the user does not own that are not the user
+    pub fn retain_matching(&self, repository: &mut Repository) {
+        for committers in repository.branch_data.values_mut() {
+            committers
+                .committers
+                .retain(|author, committer| self.matches(author, committer));
+        }
+
+        if let Some(blame) = repository.blame.as_mut() {
+            blame.owners.retain(|author, owner| {
+                self.names.contains(&author.0.to_lowercase())
+                    || owner
+                        .mails
+                        .keys()
+                        .any(|mail| self.emails.contains(&mail.to_lowercase()))
+            });
+        }
+
+        for submodule in repository.submodules.values_mut() {
+            self.retain_matching(submodule);
+        }
+        if let Some(wiki) = repository.wiki.as_mut() {
+            self.retain_matching(wiki);
+        }
+    }
+}
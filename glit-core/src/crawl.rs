@@ -0,0 +1,212 @@
+use globset::{Glob, GlobSet, GlobSetBuilder};
+use reqwest::{Client, Url};
+use serde::Serialize;
+use std::collections::{BTreeMap, HashSet, VecDeque};
+
+use crate::{
+    batch::{Extraction, TargetReport},
+    config::{CrawlConfig, OrgConfig, UserConfig},
+    forge::{forge_for, ForgeError, Relation, TargetKind},
+    org::OrgFactory,
+    target::Target,
+    user::UserFactory,
+    ExtractLog,
+};
+
+/// Owners a crawl may reach, no pattern allows everyone.
+/// Related accounts are listed by the forge of the starting user, so the crawl never leaves its host.
+#[derive(Debug, Clone, Default)]
+pub struct CrawlScope {
+    owners: Option<GlobSet>,
+}
+
+impl CrawlScope {
+    /// Owners are case insensitive globs like `acme-*`
+    pub fn new(owners: &[String]) -> Result<Self, globset::Error> {
+        let owners = match owners.is_empty() {
+            true => None,
+            false => {
+                let mut builder = GlobSetBuilder::new();
+                for owner in owners {
+                    builder.add(Glob::new(&owner.to_lowercase())?);
+                }
+                Some(builder.build()?)
+            }
+        };
+
+        Ok(Self { owners })
+    }
+
+    pub fn allows(&self, owner: &str) -> bool {
+        self.owners
+            .as_ref()
+            .is_none_or(|owners| owners.is_match(owner.to_lowercase()))
+    }
+}
+
+/// `to` was found on the profile of `from`
+#[derive(Debug, Clone, Serialize)]
+pub struct CrawlEdge {
+    pub from: String,
+    pub to: String,
+    pub relation: Relation,
+}
+
+#[derive(Serialize)]
+pub struct CrawlTarget {
+    /// Number of hops from the starting user
+    pub depth: usize,
+    #[serde(flatten)]
+    pub report: TargetReport,
+}
+
+/// Targets reached from a user, keyed by login, and how they were reached
+#[derive(Serialize)]
+pub struct Crawl {
+    pub root: String,
+    pub targets: BTreeMap<String, CrawlTarget>,
+    pub edges: Vec<CrawlEdge>,
+}
+
+/// Breadth first crawl through followers, following and organizations.
+/// Every target is scanned with the options of the starting user, organizations are not crawled further.
+pub struct Crawler {
+    user_config: UserConfig,
+    crawl_config: CrawlConfig,
+}
+
+impl Crawler {
+    pub fn new(user_config: UserConfig, crawl_config: CrawlConfig) -> Self {
+        Self {
+            user_config,
+            crawl_config,
+        }
+    }
+
+    pub async fn run(self, client: &Client) -> Crawl {
+        let root = Target::owner(self.user_config.url.as_str())
+            .unwrap_or_else(|e| panic!("Invalid user url : {}", e))
+            .owner;
+        let forge = forge_for(
+            self.user_config.forge,
+            self.user_config.backend,
+            client,
+            &self.user_config.api_config,
+            &self.user_config.url,
+            self.user_config.listing_filter,
+        );
+
+        let mut crawl = Crawl {
+            root: root.clone(),
+            targets: BTreeMap::new(),
+            edges: Vec::new(),
+        };
+        let mut visited = HashSet::from([root.clone()]);
+        let mut queue = VecDeque::from([(root, TargetKind::User, 0)]);
+
+        while let Some((login, kind, depth)) = queue.pop_front() {
+            if crawl.targets.len() >= self.crawl_config.max_targets {
+                log::info!(
+                    "Crawl stopped at {} targets, {} left unscanned",
+                    self.crawl_config.max_targets,
+                    queue.len() + 1
+                );
+                break;
+            }
+
+            let url = self.owner_url(&login);
+            log::info!("Crawl {} at depth {}", login, depth);
+            let mut report = match self.scan(client, url, kind).await {
+                Ok(extraction) => TargetReport::done(extraction),
+                Err(e) => {
+                    log::warn!("Failed to scan {} : {}", login, e);
+                    TargetReport::failed(e.to_string())
+                }
+            };
+            report.kind = Some(kind);
+            crawl
+                .targets
+                .insert(login.clone(), CrawlTarget { depth, report });
+
+            if kind != TargetKind::User || depth >= self.crawl_config.depth {
+                continue;
+            }
+
+            let related_accounts = forge
+                .list_related_accounts(&login)
+                .await
+                .unwrap_or_else(|e| {
+                    log::warn!("Failed to list accounts related to {} : {}", login, e);
+                    Vec::new()
+                });
+
+            for account in related_accounts {
+                if !self.crawl_config.scope.allows(&account.login) {
+                    log::debug!("{} is out of the crawl scope", account.login);
+                    continue;
+                }
+
+                crawl.edges.push(CrawlEdge {
+                    from: login.clone(),
+                    to: account.login.clone(),
+                    relation: account.relation,
+                });
+
+                if visited.insert(account.login.clone()) {
+                    let kind = match account.relation {
+                        Relation::Organization => TargetKind::Org,
+                        Relation::Follower | Relation::Following => TargetKind::User,
+                    };
+                    queue.push_back((account.login, kind, depth + 1));
+                }
+            }
+        }
+
+        crawl
+    }
+
+    fn owner_url(&self, login: &str) -> Url {
+        self.user_config.url.join(&format!("/{}/", login)).unwrap()
+    }
+
+    async fn scan(
+        &self,
+        client: &Client,
+        url: Url,
+        kind: TargetKind,
+    ) -> Result<Extraction, ForgeError> {
+        let user_config = UserConfig {
+            url,
+            ..self.user_config.clone()
+        };
+
+        match kind {
+            TargetKind::Org => {
+                let org_config = OrgConfig {
+                    url: user_config.url,
+                    all_branches: user_config.all_branches,
+                    log_config: user_config.log_config,
+                    forge: user_config.forge,
+                    backend: user_config.backend,
+                    api_config: user_config.api_config,
+                    listing_filter: user_config.listing_filter,
+                    selection: user_config.selection,
+                    members: false,
+                    expand_members: false,
+                };
+                let org = OrgFactory::with_config(org_config)
+                    .try_build_with_client(client)
+                    .await?;
+
+                Ok(Extraction::Org(Box::new(org.extract_log(client).await)))
+            }
+            TargetKind::User | TargetKind::Repository => {
+                let user = UserFactory::with_config(user_config)
+                    .try_build_with_client(client)
+                    .await?;
+
+                Ok(Extraction::User(user.extract_log(client).await))
+            }
+        }
+    }
+}
@@ -0,0 +1,108 @@
+use git2::{Cred, CredentialType, RemoteCallbacks};
+use reqwest::Url;
+use std::path::PathBuf;
+
+const DEFAULT_TOKEN_USERNAME: &str = "x-access-token";
+const DEFAULT_SSH_USERNAME: &str = "git";
+
+/// Credentials used to clone private repositories over HTTPS and SSH
+#[derive(Debug, Clone, Default)]
+pub struct Credentials {
+    /// Token sent as the HTTPS password
+    pub token: Option<String>,
+    /// HTTPS username used with the token. Defaults to the url username or `x-access-token`.
+    pub username: Option<String>,
+    pub ssh_key: Option<PathBuf>,
+    pub ssh_key_passphrase: Option<String>,
+    /// Hosts the token and the git credential helpers may authenticate to, usually the host of the target.
+    /// Remotes on any other host, like submodules pointing elsewhere, never receive them.
+    pub hosts: Vec<String>,
+}
+
+/// libgit2 calls the credentials callback again after each rejected attempt,
+/// so each method is tried once before giving up.
+#[derive(Default)]
+struct Attempts {
+    token: bool,
+    credential_helper: bool,
+    ssh_key: bool,
+    ssh_agent: bool,
+    username: bool,
+}
+
+impl Credentials {
+    fn trusts(&self, url: &str) -> bool {
+        Url::parse(url)
+            .ok()
+            .and_then(|url| url.host_str().map(str::to_lowercase))
+            .is_some_and(|host| self.hosts.contains(&host))
+    }
+
+    /// Register the credentials callback. Methods are tried in order :
+    /// token, git credential helpers, explicit SSH key, ssh-agent.
+    pub fn register(&self, cb: &mut RemoteCallbacks<'static>) {
+        let credentials = self.clone();
+        let mut attempts = Attempts::default();
+
+        cb.credentials(move |url, username_from_url, allowed_types| {
+            if allowed_types.contains(CredentialType::USERNAME) && !attempts.username {
+                attempts.username = true;
+                let username = username_from_url.unwrap_or(DEFAULT_SSH_USERNAME);
+                return Cred::username(username);
+            }
+
+            if allowed_types.contains(CredentialType::USER_PASS_PLAINTEXT)
+                && !credentials.trusts(url)
+            {
+                log::warn!("Refuse to send a token or stored credentials to {}", url);
+            } else if allowed_types.contains(CredentialType::USER_PASS_PLAINTEXT) {
+                if let (Some(token), false) = (&credentials.token, attempts.token) {
+                    attempts.token = true;
+                    let username = credentials
+                        .username
+                        .as_deref()
+                        .or(username_from_url)
+                        .unwrap_or(DEFAULT_TOKEN_USERNAME);
+                    log::debug!("Authenticate to {} with a token", url);
+                    return Cred::userpass_plaintext(username, token);
+                }
+
+                if !attempts.credential_helper {
+                    attempts.credential_helper = true;
+                    if let Ok(config) = git2::Config::open_default() {
+                        log::debug!("Authenticate to {} with git credential helpers", url);
+                        if let Ok(cred) = Cred::credential_helper(&config, url, username_from_url) {
+                            return Ok(cred);
+                        }
+                    }
+                }
+            }
+
+            if allowed_types.contains(CredentialType::SSH_KEY) {
+                let username = username_from_url.unwrap_or(DEFAULT_SSH_USERNAME);
+
+                if let (Some(ssh_key), false) = (&credentials.ssh_key, attempts.ssh_key) {
+                    attempts.ssh_key = true;
+                    log::debug!("Authenticate to {} with key {:?}", url, ssh_key);
+                    return Cred::ssh_key(
+                        username,
+                        None,
+                        ssh_key,
+                        credentials.ssh_key_passphrase.as_deref(),
+                    );
+                }
+
+                if !attempts.ssh_agent {
+                    attempts.ssh_agent = true;
+                    log::debug!("Authenticate to {} with ssh-agent", url);
+                    return Cred::ssh_key_from_agent(username);
+                }
+            }
+
+            Err(git2::Error::from_str(&format!(
+                "no valid credentials to clone {}",
+                url
+            )))
+        });
+    }
+}
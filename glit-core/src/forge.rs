@@ -0,0 +1,197 @@
+use async_trait::async_trait;
+use reqwest::{Client, Url};
+use serde::Serialize;
+use std::fmt;
+
+use crate::{
+    api::GithubApi,
+    bitbucket::BitbucketApi,
+    config::{ApiConfig, Backend, ForgeKind, ListingFilter},
+    contributions::Identities,
+    gitea::GiteaApi,
+    gitlab::GitlabApi,
+    graphql::{GithubGraphql, GraphqlError},
+    html::GithubHtml,
+    sourcehut::SourcehutApi,
+    target::Target,
+    types::RepositoryMetadata,
+};
+
+const GIT_SUFFIX: &str = ".git";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OwnerKind {
+    User,
+    /// Organization, gitlab group, bitbucket workspace or project
+    Org,
+}
+
+impl OwnerKind {
+    /// Path segment of the owner in github and gitea APIs
+    pub(crate) fn path_segment(&self) -> &str {
+        match self {
+            OwnerKind::User => "users",
+            OwnerKind::Org => "orgs",
+        }
+    }
+}
+
+/// What a scanned url points to
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum TargetKind {
+    Repository,
+    User,
+    Org,
+}
+
+impl From<OwnerKind> for TargetKind {
+    fn from(owner_kind: OwnerKind) -> Self {
+        match owner_kind {
+            OwnerKind::User => TargetKind::User,
+            OwnerKind::Org => TargetKind::Org,
+        }
+    }
+}
+
+/// How an account is linked to a user
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Relation {
+    Follower,
+    Following,
+    Organization,
+}
+
+/// Account found on the profile of a user
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RelatedAccount {
+    pub login: String,
+    pub relation: Relation,
+}
+
+#[derive(Debug)]
+pub enum ForgeError {
+    Request(reqwest::Error),
+    Graphql(GraphqlError),
+    /// The page layout does not match the scraping selectors
+    Scraping(String),
+    /// The forge does not expose this information
+    Unsupported(&'static str),
+}
+
+impl fmt::Display for ForgeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ForgeError::Request(e) => write!(f, "request failed : {}", e),
+            ForgeError::Graphql(e) => write!(f, "{}", e),
+            ForgeError::Scraping(page) => write!(f, "unexpected page layout at {}", page),
+            ForgeError::Unsupported(feature) => {
+                write!(f, "{} is not supported by this forge", feature)
+            }
+        }
+    }
+}
+
+impl From<reqwest::Error> for ForgeError {
+    fn from(e: reqwest::Error) -> Self {
+        ForgeError::Request(e)
+    }
+}
+
+impl From<GraphqlError> for ForgeError {
+    fn from(e: GraphqlError) -> Self {
+        ForgeError::Graphql(e)
+    }
+}
+
+/// Listing adapter of a code hosting platform.
+/// Cloning and extraction are shared, so adding a host only means implementing this trait.
+#[async_trait]
+pub trait Forge: Send + Sync {
+    /// Whether the owner is a user or an organization
+    async fn owner_kind(&self, owner: &str) -> Result<OwnerKind, ForgeError>;
+
+    /// Repositories of an owner with their fork, archived and mirror status.
+    /// Adapters narrow the listing query when the forge supports it, `ListingFilter::keeps` is applied afterwards.
+    async fn list_repositories(
+        &self,
+        owner_kind: OwnerKind,
+        owner: &str,
+    ) -> Result<Vec<RepositoryMetadata>, ForgeError>;
+
+    /// Whether a url path is a repository, a user or an organization.
+    /// A single segment is an owner, deeper paths are repositories.
+    async fn target_kind(&self, path: &str) -> Result<TargetKind, ForgeError> {
+        match path.contains('/') {
+            true => Ok(TargetKind::Repository),
+            false => Ok(self.owner_kind(path).await?.into()),
+        }
+    }
+
+    /// Logins of the public members of an organization
+    async fn list_members(&self, _org: &str) -> Result<Vec<String>, ForgeError> {
+        Err(ForgeError::Unsupported("member listing"))
+    }
+
+    /// Followers, followed users and organizations of a user
+    async fn list_related_accounts(&self, _user: &str) -> Result<Vec<RelatedAccount>, ForgeError> {
+        Err(ForgeError::Unsupported(
+            "followers and organizations listing",
+        ))
+    }
+
+    /// Repositories of other owners the user pushed to or had pull requests merged in
+    async fn list_contributions(&self, _user: &str) -> Result<Vec<RepositoryMetadata>, ForgeError> {
+        Err(ForgeError::Unsupported("contributions listing"))
+    }
+
+    /// Names and emails the forge publishes for a user, the login alone by default
+    async fn known_identities(&self, user: &str) -> Result<Identities, ForgeError> {
+        Ok(Identities::new(user))
+    }
+
+    /// Url handed to `RepositoryFactory` to clone a listed repository
+    fn clone_url(&self, repository: &RepositoryMetadata) -> Url {
+        repository.repo_url()
+    }
+}
+
+/// Listing adapter of a forge. The backend only applies to github, other forges always use their API.
+pub fn forge_for<'a>(
+    forge_kind: ForgeKind,
+    backend: Backend,
+    client: &'a Client,
+    api_config: &'a ApiConfig,
+    owner_url: &'a Url,
+    listing_filter: ListingFilter,
+) -> Box<dyn Forge + 'a> {
+    match (forge_kind, backend) {
+        (ForgeKind::Github, Backend::Html) => {
+            Box::new(GithubHtml::new(client, owner_url, listing_filter))
+        }
+        (ForgeKind::Github, Backend::Api) => {
+            Box::new(GithubApi::new(client, api_config, listing_filter))
+        }
+        (ForgeKind::Github, Backend::Graphql) => {
+            Box::new(GithubGraphql::new(client, api_config, listing_filter))
+        }
+        (ForgeKind::Gitlab, _) => Box::new(GitlabApi::new(client, api_config, listing_filter)),
+        (ForgeKind::Gitea, _) => Box::new(GiteaApi::new(client, api_config)),
+        (ForgeKind::Bitbucket, _) => Box::new(BitbucketApi::new(client, api_config)),
+        (ForgeKind::Sourcehut, _) => Box::new(SourcehutApi::new(client, api_config, owner_url)),
+    }
+}
+
+/// Probe what a target points to. Only web urls are probed,
+/// `.git` urls and ssh, git or file targets are always repositories.
+pub async fn probe_target(forge: &dyn Forge, target: &Target) -> Result<TargetKind, ForgeError> {
+    let is_web_url = matches!(target.scheme.as_str(), "https" | "http");
+    if !is_web_url || target.clone_url.path().ends_with(GIT_SUFFIX) {
+        return Ok(TargetKind::Repository);
+    }
+
+    let kind = forge.target_kind(&target.owner).await?;
+    log::info!("{} is a {:?} target", target.owner, kind);
+    Ok(kind)
+}
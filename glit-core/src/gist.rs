@@ -0,0 +1,49 @@
+use reqwest::{Client, Url};
+use serde::Deserialize;
+
+use crate::{
+    api::GithubApi,
+    config::{ApiConfig, ListingFilter},
+};
+
+pub const GIST_PREFIX: &str = "gist:";
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct Gist {
+    pub id: String,
+    pub html_url: String,
+}
+
+impl Gist {
+    /// Clonable url of the gist, in the `<host>/<user>/<id>/` shape expected by `RepositoryFactory`
+    pub fn clone_url(&self, user: &str) -> Option<Url> {
+        let mut clone_url = Url::parse(&self.html_url).ok()?;
+        let prefix = clone_url
+            .path()
+            .trim_end_matches('/')
+            .strip_suffix(&self.id)?
+            .to_string();
+
+        clone_url.set_path(&format!("{}{}/{}/", prefix, user, self.id));
+        Some(clone_url)
+    }
+
+    /// Key of the gist in `User.repositories_data`
+    pub fn repo_name(&self) -> String {
+        format!("{}{}", GIST_PREFIX, self.id)
+    }
+}
+
+/// List all public gists of a user with the REST API
+pub async fn list_gists(client: &Client, api_config: &ApiConfig, user: &str) -> Vec<Gist> {
+    let api = GithubApi::new(client, api_config, ListingFilter::default());
+    let gists_url = api.endpoint(&format!("users/{}/gists", user));
+
+    match api.paginate::<Gist>(gists_url).await {
+        Ok(gists) => gists,
+        Err(e) => {
+            log::error!("Failed to list gists of {} : {:?}", user, e);
+            Vec::new()
+        }
+    }
+}
@@ -0,0 +1,175 @@
+use async_trait::async_trait;
+use reqwest::{header::AUTHORIZATION, Client, RequestBuilder, StatusCode, Url};
+use serde::{de::DeserializeOwned, Deserialize};
+use std::convert::identity;
+
+use crate::{
+    api::paginate_links,
+    config::ApiConfig,
+    forge::{Forge, ForgeError, OwnerKind, RelatedAccount, Relation},
+    types::RepositoryMetadata,
+};
+
+const GITEA_API_PATH: &str = "/api/v1/";
+/// Default `MAX_RESPONSE_ITEMS` of a Gitea or Forgejo instance
+const ITEMS_PER_PAGE: usize = 50;
+
+/// Base url of the v1 API of a Gitea or Forgejo instance : `<host>/api/v1/`
+pub fn api_base_url(url: &Url) -> Url {
+    let mut base_url = url.clone();
+    base_url.set_path(GITEA_API_PATH);
+    base_url.set_query(None);
+    base_url
+}
+
+/// Repository as returned by the `/users/{u}/repos` and `/orgs/{o}/repos` endpoints
+#[derive(Debug, Clone, Deserialize)]
+struct GiteaRepository {
+    name: String,
+    html_url: String,
+    default_branch: Option<String>,
+    fork: bool,
+    #[serde(default)]
+    archived: bool,
+    #[serde(default)]
+    mirror: bool,
+    size: Option<u64>,
+    updated_at: Option<String>,
+}
+
+impl From<GiteaRepository> for RepositoryMetadata {
+    fn from(repository: GiteaRepository) -> Self {
+        Self {
+            name: repository.name,
+            url: repository.html_url,
+            default_branch: repository.default_branch,
+            fork: repository.fork,
+            archived: repository.archived,
+            mirror: repository.mirror,
+            size_kb: repository.size,
+            pushed_at: repository.updated_at,
+        }
+    }
+}
+
+/// User as returned by the members, followers and following endpoints
+#[derive(Debug, Clone, Deserialize)]
+struct GiteaUser {
+    login: String,
+}
+
+/// Organization as returned by the `/users/{u}/orgs` endpoint
+#[derive(Debug, Clone, Deserialize)]
+struct GiteaOrganization {
+    name: String,
+}
+
+pub struct GiteaApi<'a> {
+    client: &'a Client,
+    config: &'a ApiConfig,
+}
+
+impl<'a> GiteaApi<'a> {
+    pub fn new(client: &'a Client, config: &'a ApiConfig) -> Self {
+        Self { client, config }
+    }
+
+    fn get(&self, url: Url) -> RequestBuilder {
+        let request = self.client.get(url);
+        match &self.config.token {
+            Some(token) => request.header(AUTHORIZATION, format!("token {}", token)),
+            None => request,
+        }
+    }
+
+    async fn paginate<T: DeserializeOwned>(&self, url: Url) -> Result<Vec<T>, reqwest::Error> {
+        paginate_links(
+            |url| self.get(url),
+            url,
+            ("limit", ITEMS_PER_PAGE),
+            identity,
+            None,
+        )
+        .await
+    }
+
+    fn endpoint(&self, path: &str) -> Url {
+        self.config.base_url.join(path).unwrap()
+    }
+}
+
+#[async_trait]
+impl Forge for GiteaApi<'_> {
+    /// Organizations are found at `/orgs/{name}`, users are a 404 there
+    async fn owner_kind(&self, owner: &str) -> Result<OwnerKind, ForgeError> {
+        let url = self.endpoint(&format!("orgs/{}", owner));
+        let resp = self.get(url).send().await?;
+
+        match resp.status() {
+            StatusCode::NOT_FOUND => Ok(OwnerKind::User),
+            _ => resp
+                .error_for_status()
+                .map(|_| OwnerKind::Org)
+                .map_err(Into::into),
+        }
+    }
+
+    /// List repositories of a user or an organization
+    async fn list_repositories(
+        &self,
+        owner_kind: OwnerKind,
+        owner: &str,
+    ) -> Result<Vec<RepositoryMetadata>, ForgeError> {
+        let url = self.endpoint(&format!("{}/{}/repos", owner_kind.path_segment(), owner));
+        let repositories = self
+            .paginate::<GiteaRepository>(url)
+            .await?
+            .into_iter()
+            .map(RepositoryMetadata::from)
+            .collect::<Vec<_>>();
+
+        log::info!(
+            "Gitea API listed {} repositories for {}",
+            repositories.len(),
+            owner
+        );
+        Ok(repositories)
+    }
+
+    async fn list_members(&self, org: &str) -> Result<Vec<String>, ForgeError> {
+        let url = self.endpoint(&format!("orgs/{}/public_members", org));
+        let members = self.paginate::<GiteaUser>(url).await?;
+
+        Ok(members.into_iter().map(|member| member.login).collect())
+    }
+
+    async fn list_related_accounts(&self, user: &str) -> Result<Vec<RelatedAccount>, ForgeError> {
+        let mut related_accounts = Vec::new();
+
+        for (relation, path) in [
+            (Relation::Follower, "followers"),
+            (Relation::Following, "following"),
+        ] {
+            let url = self.endpoint(&format!("users/{}/{}", user, path));
+            let users = self.paginate::<GiteaUser>(url).await?;
+
+            related_accounts.extend(users.into_iter().map(|account| RelatedAccount {
+                login: account.login,
+                relation,
+            }));
+        }
+
+        let url = self.endpoint(&format!("users/{}/orgs", user));
+        let organizations = self.paginate::<GiteaOrganization>(url).await?;
+        related_accounts.extend(
+            organizations
+                .into_iter()
+                .map(|organization| RelatedAccount {
+                    login: organization.name,
+                    relation: Relation::Organization,
+                }),
+        );
+
+        Ok(related_accounts)
+    }
+}
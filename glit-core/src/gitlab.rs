@@ -0,0 +1,171 @@
+use async_trait::async_trait;
+use reqwest::{Client, RequestBuilder, StatusCode, Url};
+use serde::{de::DeserializeOwned, Deserialize};
+use std::convert::identity;
+
+use crate::{
+    api::paginate_links,
+    config::{ApiConfig, ListingFilter},
+    forge::{Forge, ForgeError, OwnerKind, TargetKind},
+    types::RepositoryMetadata,
+};
+
+const GITLAB_API_PATH: &str = "/api/v4/";
+const PRIVATE_TOKEN: &str = "PRIVATE-TOKEN";
+const ITEMS_PER_PAGE: usize = 100;
+
+/// Base url of the v4 API of a gitlab.com or self-managed instance : `<host>/api/v4/`
+pub fn api_base_url(url: &Url) -> Url {
+    let mut base_url = url.clone();
+    base_url.set_path(GITLAB_API_PATH);
+    base_url.set_query(None);
+    base_url
+}
+
+/// Project as returned by the `/users/{u}/projects` and `/groups/{g}/projects` endpoints
+#[derive(Debug, Clone, Deserialize)]
+struct Project {
+    path: String,
+    http_url_to_repo: String,
+    default_branch: Option<String>,
+    forked_from_project: Option<ForkedFrom>,
+    #[serde(default)]
+    archived: bool,
+    /// Only visible to maintainers of the project
+    #[serde(default)]
+    mirror: bool,
+    last_activity_at: Option<String>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+struct ForkedFrom {}
+
+impl From<Project> for RepositoryMetadata {
+    fn from(project: Project) -> Self {
+        Self {
+            name: project.path,
+            url: project.http_url_to_repo,
+            default_branch: project.default_branch,
+            fork: project.forked_from_project.is_some(),
+            archived: project.archived,
+            mirror: project.mirror,
+            size_kb: None,
+            pushed_at: project.last_activity_at,
+        }
+    }
+}
+
+/// Member as returned by the `/groups/{g}/members` endpoint
+#[derive(Debug, Clone, Deserialize)]
+struct Member {
+    username: String,
+}
+
+pub struct GitlabApi<'a> {
+    client: &'a Client,
+    config: &'a ApiConfig,
+    listing_filter: ListingFilter,
+}
+
+impl<'a> GitlabApi<'a> {
+    pub fn new(client: &'a Client, config: &'a ApiConfig, listing_filter: ListingFilter) -> Self {
+        Self {
+            client,
+            config,
+            listing_filter,
+        }
+    }
+
+    fn get(&self, url: Url) -> RequestBuilder {
+        let request = self.client.get(url);
+        match &self.config.token {
+            Some(token) => request.header(PRIVATE_TOKEN, token),
+            None => request,
+        }
+    }
+
+    async fn paginate<T: DeserializeOwned>(&self, url: Url) -> Result<Vec<T>, reqwest::Error> {
+        paginate_links(
+            |url| self.get(url),
+            url,
+            ("per_page", ITEMS_PER_PAGE),
+            identity,
+            None,
+        )
+        .await
+    }
+
+    fn endpoint(&self, path: &str) -> Url {
+        self.config.base_url.join(path).unwrap()
+    }
+}
+
+#[async_trait]
+impl Forge for GitlabApi<'_> {
+    /// Groups, nested or not, are found at `/groups/{path}`, users are a 404 there
+    async fn owner_kind(&self, owner: &str) -> Result<OwnerKind, ForgeError> {
+        let url = self.endpoint(&format!("groups/{}", owner.replace('/', "%2F")));
+        let resp = self.get(url).send().await?;
+
+        match resp.status() {
+            StatusCode::NOT_FOUND => Ok(OwnerKind::User),
+            _ => resp
+                .error_for_status()
+                .map(|_| OwnerKind::Org)
+                .map_err(Into::into),
+        }
+    }
+
+    /// Groups can be nested, so a deeper path is a repository only when it is not a group
+    async fn target_kind(&self, path: &str) -> Result<TargetKind, ForgeError> {
+        match (self.owner_kind(path).await?, path.contains('/')) {
+            (OwnerKind::Org, _) => Ok(TargetKind::Org),
+            (OwnerKind::User, false) => Ok(TargetKind::User),
+            (OwnerKind::User, true) => Ok(TargetKind::Repository),
+        }
+    }
+
+    /// List projects of a user, or of a group and all its nested subgroups.
+    /// Group owners are full paths like `group/subgroup`.
+    async fn list_repositories(
+        &self,
+        owner_kind: OwnerKind,
+        owner: &str,
+    ) -> Result<Vec<RepositoryMetadata>, ForgeError> {
+        let mut url = match owner_kind {
+            OwnerKind::User => self.endpoint(&format!("users/{}/projects", owner)),
+            OwnerKind::Org => {
+                self.endpoint(&format!("groups/{}/projects", owner.replace('/', "%2F")))
+            }
+        };
+        if owner_kind == OwnerKind::Org {
+            url.query_pairs_mut()
+                .append_pair("include_subgroups", "true");
+        }
+        if self.listing_filter.exclude_archived {
+            url.query_pairs_mut().append_pair("archived", "false");
+        }
+
+        let projects = self
+            .paginate::<Project>(url)
+            .await?
+            .into_iter()
+            .map(RepositoryMetadata::from)
+            .collect::<Vec<_>>();
+
+        log::info!(
+            "GitLab API listed {} projects for {}",
+            projects.len(),
+            owner
+        );
+        Ok(projects)
+    }
+
+    /// Direct members of a group, visible to the token
+    async fn list_members(&self, org: &str) -> Result<Vec<String>, ForgeError> {
+        let url = self.endpoint(&format!("groups/{}/members", org.replace('/', "%2F")));
+        let members = self.paginate::<Member>(url).await?;
+
+        Ok(members.into_iter().map(|member| member.username).collect())
+    }
+}
@@ -0,0 +1,401 @@
+use async_trait::async_trait;
+use reqwest::{Client, Url};
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use std::fmt;
+
+use crate::{
+    config::{ApiConfig, ListingFilter},
+    forge::{Forge, ForgeError, OwnerKind},
+    types::RepositoryMetadata,
+};
+
+const REPOSITORIES_QUERY: &str = r#"
+query($login: String!, $cursor: String, $isFork: Boolean, $isArchived: Boolean) {
+  rateLimit { cost remaining resetAt }
+  repositoryOwner(login: $login) {
+    repositories(first: 100, after: $cursor, isFork: $isFork, isArchived: $isArchived, ownerAffiliations: OWNER) {
+      totalCount
+      pageInfo { hasNextPage endCursor }
+      nodes {
+        name
+        url
+        isFork
+        isArchived
+        isMirror
+        diskUsage
+        pushedAt
+        defaultBranchRef { name }
+      }
+    }
+  }
+}
+"#;
+
+const OWNER_KIND_QUERY: &str = r#"
+query($login: String!) {
+  repositoryOwner(login: $login) { __typename }
+}
+"#;
+
+const MEMBERS_QUERY: &str = r#"
+query($login: String!, $cursor: String) {
+  organization(login: $login) {
+    membersWithRole(first: 100, after: $cursor) {
+      pageInfo { hasNextPage endCursor }
+      nodes { login }
+    }
+  }
+}
+"#;
+
+#[derive(Debug)]
+pub enum GraphqlError {
+    MissingToken,
+    Request(reqwest::Error),
+    Response(Vec<String>),
+    UnknownOwner(String),
+}
+
+impl fmt::Display for GraphqlError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            GraphqlError::MissingToken => write!(f, "the GraphQL API requires a token"),
+            GraphqlError::Request(e) => write!(f, "request failed : {}", e),
+            GraphqlError::Response(messages) => write!(f, "{}", messages.join(", ")),
+            GraphqlError::UnknownOwner(owner) => write!(f, "no user or organization {}", owner),
+        }
+    }
+}
+
+impl From<reqwest::Error> for GraphqlError {
+    fn from(e: reqwest::Error) -> Self {
+        GraphqlError::Request(e)
+    }
+}
+
+/// Query and its variables, shared by the GraphQL APIs of GitHub and sourcehut
+#[derive(Serialize)]
+pub(crate) struct GraphqlRequest<'a, V> {
+    pub query: &'a str,
+    pub variables: V,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct Variables<'a> {
+    login: &'a str,
+    cursor: Option<String>,
+    /// `None` lists both forks and sources
+    #[serde(skip_serializing_if = "Option::is_none")]
+    is_fork: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    is_archived: Option<bool>,
+}
+
+#[derive(Deserialize)]
+struct GraphqlResponse<T> {
+    data: Option<T>,
+    errors: Option<Vec<GraphqlMessage>>,
+}
+
+#[derive(Deserialize)]
+struct GraphqlMessage {
+    message: String,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct Data {
+    rate_limit: Option<RateLimit>,
+    repository_owner: Option<RepositoryOwner>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct RateLimit {
+    cost: u64,
+    remaining: u64,
+    reset_at: String,
+}
+
+#[derive(Deserialize)]
+struct RepositoryOwner {
+    repositories: RepositoryConnection,
+}
+
+#[derive(Deserialize)]
+struct MembersData {
+    organization: Option<Organization>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct Organization {
+    members_with_role: MemberConnection,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct MemberConnection {
+    page_info: PageInfo,
+    nodes: Vec<MemberNode>,
+}
+
+#[derive(Deserialize)]
+struct MemberNode {
+    login: String,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct OwnerKindData {
+    repository_owner: Option<OwnerTypename>,
+}
+
+#[derive(Deserialize)]
+struct OwnerTypename {
+    #[serde(rename = "__typename")]
+    typename: String,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct RepositoryConnection {
+    total_count: usize,
+    page_info: PageInfo,
+    nodes: Vec<RepositoryNode>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct PageInfo {
+    has_next_page: bool,
+    end_cursor: Option<String>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct RepositoryNode {
+    name: String,
+    url: String,
+    is_fork: bool,
+    is_archived: bool,
+    is_mirror: bool,
+    disk_usage: Option<u64>,
+    pushed_at: Option<String>,
+    default_branch_ref: Option<BranchRef>,
+}
+
+#[derive(Deserialize)]
+struct BranchRef {
+    name: String,
+}
+
+impl From<RepositoryNode> for RepositoryMetadata {
+    fn from(node: RepositoryNode) -> Self {
+        Self {
+            name: node.name,
+            url: node.url,
+            default_branch: node.default_branch_ref.map(|branch| branch.name),
+            fork: node.is_fork,
+            archived: node.is_archived,
+            mirror: node.is_mirror,
+            size_kb: node.disk_usage,
+            pushed_at: node.pushed_at,
+        }
+    }
+}
+
+/// GraphQL endpoint next to the REST API : `api.github.com/graphql` or `<host>/api/graphql` for GitHub Enterprise Server
+pub fn graphql_url(api_base_url: &Url) -> Url {
+    if api_base_url
+        .path()
+        .trim_end_matches('/')
+        .ends_with("/api/v3")
+    {
+        api_base_url.join("../graphql").unwrap()
+    } else {
+        api_base_url.join("graphql").unwrap()
+    }
+}
+
+pub struct GithubGraphql<'a> {
+    client: &'a Client,
+    config: &'a ApiConfig,
+    listing_filter: ListingFilter,
+}
+
+impl<'a> GithubGraphql<'a> {
+    pub fn new(client: &'a Client, config: &'a ApiConfig, listing_filter: ListingFilter) -> Self {
+        Self {
+            client,
+            config,
+            listing_filter,
+        }
+    }
+
+    async fn post<T: DeserializeOwned>(
+        &self,
+        request: &GraphqlRequest<'_, Variables<'_>>,
+    ) -> Result<T, GraphqlError> {
+        post_query(
+            self.client,
+            graphql_url(&self.config.base_url),
+            self.config.token.as_deref(),
+            request,
+        )
+        .await
+    }
+}
+
+/// Send a query with a bearer token, GraphQL errors are reported even with a 200 status
+pub(crate) async fn post_query<T: DeserializeOwned>(
+    client: &Client,
+    url: Url,
+    token: Option<&str>,
+    request: &GraphqlRequest<'_, impl Serialize>,
+) -> Result<T, GraphqlError> {
+    let token = token.ok_or(GraphqlError::MissingToken)?;
+
+    let response = client
+        .post(url)
+        .bearer_auth(token)
+        .json(request)
+        .send()
+        .await?
+        .error_for_status()?
+        .json::<GraphqlResponse<T>>()
+        .await?;
+
+    if let Some(errors) = response.errors {
+        return Err(GraphqlError::Response(
+            errors.into_iter().map(|error| error.message).collect(),
+        ));
+    }
+
+    response
+        .data
+        .ok_or_else(|| GraphqlError::Response(vec!["empty response".to_string()]))
+}
+
+#[async_trait]
+impl Forge for GithubGraphql<'_> {
+    async fn owner_kind(&self, owner: &str) -> Result<OwnerKind, ForgeError> {
+        let request = GraphqlRequest {
+            query: OWNER_KIND_QUERY,
+            variables: Variables {
+                login: owner,
+                cursor: None,
+                is_fork: None,
+                is_archived: None,
+            },
+        };
+
+        let data = self.post::<OwnerKindData>(&request).await?;
+        match data.repository_owner {
+            Some(owner) if owner.typename == "Organization" => Ok(OwnerKind::Org),
+            Some(_) => Ok(OwnerKind::User),
+            None => Err(GraphqlError::UnknownOwner(owner.to_string()).into()),
+        }
+    }
+
+    /// List repositories of a user or an organization, 100 per query.
+    /// The rate limit cost of every page is accumulated and logged.
+    async fn list_repositories(
+        &self,
+        _owner_kind: OwnerKind,
+        owner: &str,
+    ) -> Result<Vec<RepositoryMetadata>, ForgeError> {
+        let mut repositories = Vec::new();
+        let mut cursor: Option<String> = None;
+        let mut total_cost = 0;
+
+        loop {
+            let request = GraphqlRequest {
+                query: REPOSITORIES_QUERY,
+                variables: Variables {
+                    login: owner,
+                    cursor: cursor.clone(),
+                    is_fork: (!self.listing_filter.include_forks).then_some(false),
+                    is_archived: self.listing_filter.exclude_archived.then_some(false),
+                },
+            };
+
+            let data = self.post::<Data>(&request).await?;
+
+            if let Some(rate_limit) = data.rate_limit {
+                total_cost += rate_limit.cost;
+                log::info!(
+                    "GraphQL page cost {} - remaining {} until {}",
+                    rate_limit.cost,
+                    rate_limit.remaining,
+                    rate_limit.reset_at
+                );
+                if rate_limit.remaining < rate_limit.cost {
+                    log::warn!(
+                        "GraphQL rate limit almost exhausted, reset at {}",
+                        rate_limit.reset_at
+                    );
+                }
+            }
+
+            let connection = data
+                .repository_owner
+                .ok_or_else(|| GraphqlError::UnknownOwner(owner.to_string()))?
+                .repositories;
+
+            if repositories.is_empty() {
+                repositories.reserve(connection.total_count);
+            }
+            repositories.extend(connection.nodes.into_iter().map(RepositoryMetadata::from));
+
+            if !connection.page_info.has_next_page {
+                break;
+            }
+            cursor = connection.page_info.end_cursor;
+        }
+
+        log::info!(
+            "GraphQL listed {} repositories for {} with a total cost of {}",
+            repositories.len(),
+            owner,
+            total_cost
+        );
+
+        Ok(repositories)
+    }
+
+    /// Members visible to the token, public members only for outsiders
+    async fn list_members(&self, org: &str) -> Result<Vec<String>, ForgeError> {
+        let mut members = Vec::new();
+        let mut cursor: Option<String> = None;
+
+        loop {
+            let request = GraphqlRequest {
+                query: MEMBERS_QUERY,
+                variables: Variables {
+                    login: org,
+                    cursor: cursor.clone(),
+                    is_fork: None,
+                    is_archived: None,
+                },
+            };
+
+            let connection = self
+                .post::<MembersData>(&request)
+                .await?
+                .organization
+                .ok_or_else(|| GraphqlError::UnknownOwner(org.to_string()))?
+                .members_with_role;
+
+            members.extend(connection.nodes.into_iter().map(|member| member.login));
+
+            if !connection.page_info.has_next_page {
+                break;
+            }
+            cursor = connection.page_info.end_cursor;
+        }
+
+        Ok(members)
+    }
+}
@@ -0,0 +1,307 @@
+use async_trait::async_trait;
+use futures_util::future::join_all;
+use reqwest::{Client, Url};
+use scraper::{Html, Selector};
+use std::collections::HashSet;
+
+use crate::{
+    config::ListingFilter,
+    forge::{Forge, ForgeError, OwnerKind, RelatedAccount, Relation},
+    types::RepositoryMetadata,
+};
+
+const NUMBER_OF_REPO_PER_PAGE: usize = 30;
+
+/// Values of the `type` filter of the repositories pages
+const SOURCE_TYPE: &str = "source";
+const FORK_TYPE: &str = "fork";
+const ARCHIVED_TYPE: &str = "archived";
+const MIRROR_TYPE: &str = "mirror";
+
+const USER_COUNT_SELECTOR: &str =
+    r#"div > div.user-repo-search-results-summary > strong:nth-child(1)"#;
+const USER_REPOSITORY_SELECTOR: &str = r#"turbo-frame > div > div > ul > li > div > div > h3 > a"#;
+const ORG_COUNT_SELECTOR: &str =
+    r#"main > div > div > div > div > div > div > div > span > strong"#;
+const ORG_REPOSITORY_SELECTOR: &str =
+    r#"main > div > div > div > div > div > div > ul > li > div > div > div > h3 > a"#;
+const USER_LINK_SELECTOR: &str = r#"a[data-hovercard-type="user"]"#;
+const ORGANIZATION_LINK_SELECTOR: &str = r#"a[data-hovercard-type="organization"]"#;
+
+/// Scrape the repositories pages of github.com, no token needed
+pub struct GithubHtml<'a> {
+    client: &'a Client,
+    owner_url: &'a Url,
+    listing_filter: ListingFilter,
+}
+
+impl<'a> GithubHtml<'a> {
+    pub fn new(client: &'a Client, owner_url: &'a Url, listing_filter: ListingFilter) -> Self {
+        Self {
+            client,
+            owner_url,
+            listing_filter,
+        }
+    }
+
+    /// First page of the repositories of an owner with the given `type` filter
+    fn repositories_page(&self, owner_kind: OwnerKind, owner: &str, repository_type: &str) -> Url {
+        let page_url = match owner_kind {
+            OwnerKind::User => format!(
+                "{}?tab=repositories&type={}",
+                self.owner_url, repository_type
+            ),
+            OwnerKind::Org => format!("/orgs/{}/repositories?q=&type={}", owner, repository_type),
+        };
+
+        // Keep the port of GitHub Enterprise Server instances
+        self.owner_url.join(&page_url).unwrap()
+    }
+
+    async fn document(&self, url: Url) -> Result<Html, ForgeError> {
+        let text = self.client.get(url).send().await?.text().await?;
+        Ok(Html::parse_document(&text))
+    }
+
+    async fn repositories_count(
+        &self,
+        page_url: &Url,
+        owner_kind: OwnerKind,
+    ) -> Result<usize, ForgeError> {
+        let selector = match owner_kind {
+            OwnerKind::User => USER_COUNT_SELECTOR,
+            OwnerKind::Org => ORG_COUNT_SELECTOR,
+        };
+        let selector = Selector::parse(selector).unwrap();
+
+        let document = self.document(page_url.clone()).await?;
+        document
+            .select(&selector)
+            .next()
+            .and_then(|count| count.inner_html().trim().replace(',', "").parse().ok())
+            .ok_or_else(|| ForgeError::Scraping(page_url.to_string()))
+    }
+
+    fn pages_urls(page_url: &Url, repo_count: usize) -> Vec<Url> {
+        let pages_count = repo_count.div_ceil(NUMBER_OF_REPO_PER_PAGE);
+
+        (1..pages_count + 1)
+            .map(|page| Url::parse(&format!("{}&page={}", page_url, page)).unwrap())
+            .collect()
+    }
+
+    async fn scrape_page(
+        &self,
+        page_url: Url,
+        selector: &Selector,
+    ) -> Result<Vec<RepositoryMetadata>, ForgeError> {
+        let document = self.document(page_url).await?;
+
+        let repositories = document
+            .select(selector)
+            .filter_map(|link| {
+                let href = link.value().attr("href")?;
+                let url = self.owner_url.join(href).ok()?;
+                let name = url.path_segments()?.next_back()?.to_string();
+
+                Some(RepositoryMetadata {
+                    name,
+                    url: url.to_string(),
+                    ..Default::default()
+                })
+            })
+            .collect();
+
+        Ok(repositories)
+    }
+
+    /// Scrape every page of one `type` of repositories
+    async fn list_type(
+        &self,
+        owner_kind: OwnerKind,
+        owner: &str,
+        repository_type: &str,
+    ) -> Result<Vec<RepositoryMetadata>, ForgeError> {
+        let page_url = self.repositories_page(owner_kind, owner, repository_type);
+        let repo_count = self.repositories_count(&page_url, owner_kind).await?;
+
+        let selector = match owner_kind {
+            OwnerKind::User => USER_REPOSITORY_SELECTOR,
+            OwnerKind::Org => ORG_REPOSITORY_SELECTOR,
+        };
+        let selector = Selector::parse(selector).unwrap();
+
+        let pages = Self::pages_urls(&page_url, repo_count)
+            .into_iter()
+            .map(|page| self.scrape_page(page, &selector));
+
+        let mut repositories = Vec::with_capacity(repo_count);
+        for page in join_all(pages).await {
+            repositories.extend(page?);
+        }
+
+        Ok(repositories)
+    }
+
+    /// Logins linked from a paginated page, pages are scraped until one brings no new login
+    async fn scrape_logins(&self, url: Url, selector: &str) -> Result<Vec<String>, ForgeError> {
+        let selector = Selector::parse(selector).unwrap();
+        let mut logins: Vec<String> = Vec::new();
+
+        for page in 1.. {
+            let mut page_url = url.clone();
+            page_url
+                .query_pairs_mut()
+                .append_pair("page", &page.to_string());
+            let document = self.document(page_url).await?;
+
+            let page_logins = document
+                .select(&selector)
+                .filter_map(|link| link.value().attr("href"))
+                .map(|href| href.trim_matches('/').to_string())
+                .filter(|login| !login.is_empty() && !login.contains('/'))
+                .collect::<Vec<String>>();
+
+            let known_count = logins.len();
+            for login in page_logins {
+                if !logins.contains(&login) {
+                    logins.push(login);
+                }
+            }
+            if logins.len() == known_count {
+                break;
+            }
+        }
+
+        Ok(logins)
+    }
+
+    /// Forks, archived repositories and mirrors are often absent, an empty page has no count to scrape
+    async fn list_optional_type(
+        &self,
+        owner_kind: OwnerKind,
+        owner: &str,
+        repository_type: &str,
+    ) -> Vec<RepositoryMetadata> {
+        self.list_type(owner_kind, owner, repository_type)
+            .await
+            .unwrap_or_else(|e| {
+                log::info!("No {} repositories for {} : {}", repository_type, owner, e);
+                Vec::new()
+            })
+    }
+
+    /// Urls of one optional `type` of repositories
+    async fn listed_urls(
+        &self,
+        owner_kind: OwnerKind,
+        owner: &str,
+        repository_type: &str,
+    ) -> HashSet<String> {
+        self.list_optional_type(owner_kind, owner, repository_type)
+            .await
+            .into_iter()
+            .map(|repository| repository.url)
+            .collect()
+    }
+}
+
+#[async_trait]
+impl Forge for GithubHtml<'_> {
+    /// Organizations have a `/orgs/{owner}` page, users do not
+    async fn owner_kind(&self, owner: &str) -> Result<OwnerKind, ForgeError> {
+        let org_url = self.owner_url.join(&format!("/orgs/{}", owner)).unwrap();
+        let resp = self.client.get(org_url).send().await?;
+
+        match resp.status().is_success() {
+            true => Ok(OwnerKind::Org),
+            false => Ok(OwnerKind::User),
+        }
+    }
+
+    async fn list_repositories(
+        &self,
+        owner_kind: OwnerKind,
+        owner: &str,
+    ) -> Result<Vec<RepositoryMetadata>, ForgeError> {
+        let mut repositories = match self.listing_filter.only_mirrors {
+            true => {
+                self.list_optional_type(owner_kind, owner, MIRROR_TYPE)
+                    .await
+            }
+            false => self.list_type(owner_kind, owner, SOURCE_TYPE).await?,
+        };
+
+        if self.listing_filter.include_forks && !self.listing_filter.only_mirrors {
+            let mut forks = self.list_optional_type(owner_kind, owner, FORK_TYPE).await;
+            for fork in forks.iter_mut() {
+                fork.fork = true;
+            }
+            repositories.extend(forks);
+        }
+
+        // Archived repositories and mirrors are only known from their own listing, scraped when something
+        // was listed to carry the status. With --only-mirrors every listed repository is a mirror.
+        if !repositories.is_empty() {
+            let archived = self.listed_urls(owner_kind, owner, ARCHIVED_TYPE).await;
+            let mirrors = match self.listing_filter.only_mirrors {
+                true => None,
+                false => Some(self.listed_urls(owner_kind, owner, MIRROR_TYPE).await),
+            };
+
+            for repository in repositories.iter_mut() {
+                repository.archived = archived.contains(&repository.url);
+                repository.mirror = mirrors
+                    .as_ref()
+                    .is_none_or(|mirrors| mirrors.contains(&repository.url));
+            }
+        }
+
+        log::info!("Scraped {} repositories for {}", repositories.len(), owner);
+        Ok(repositories)
+    }
+
+    /// Scrape the `/orgs/{org}/people` pages
+    async fn list_members(&self, org: &str) -> Result<Vec<String>, ForgeError> {
+        let people_url = self
+            .owner_url
+            .join(&format!("/orgs/{}/people", org))
+            .unwrap();
+        let members = self.scrape_logins(people_url, USER_LINK_SELECTOR).await?;
+
+        log::info!("Scraped {} members of {}", members.len(), org);
+        Ok(members)
+    }
+
+    /// Scrape the followers and following tabs and the organizations of the profile page
+    async fn list_related_accounts(&self, user: &str) -> Result<Vec<RelatedAccount>, ForgeError> {
+        let profile_url = self.owner_url.join(&format!("/{}", user)).unwrap();
+        let mut related_accounts = Vec::new();
+
+        for (relation, tab) in [
+            (Relation::Follower, "followers"),
+            (Relation::Following, "following"),
+        ] {
+            let mut tab_url = profile_url.clone();
+            tab_url.query_pairs_mut().append_pair("tab", tab);
+
+            let logins = self.scrape_logins(tab_url, USER_LINK_SELECTOR).await?;
+            related_accounts.extend(
+                logins
+                    .into_iter()
+                    .filter(|login| login != user)
+                    .map(|login| RelatedAccount { login, relation }),
+            );
+        }
+
+        let organizations = self
+            .scrape_logins(profile_url, ORGANIZATION_LINK_SELECTOR)
+            .await?;
+        related_accounts.extend(organizations.into_iter().map(|login| RelatedAccount {
+            login,
+            relation: Relation::Organization,
+        }));
+
+        Ok(related_accounts)
+    }
+}
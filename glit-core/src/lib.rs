@@ -1,186 +1,127 @@
-use crate::{config::RepositoryConfig, repo::RepositoryFactory};
+use crate::{
+    config::{LogConfig, RepositoryConfig},
+    repo::RepositoryFactory,
+};
 use ahash::RandomState;
 use async_trait::async_trait;
-use crossbeam_channel::bounded;
 use dashmap::DashMap;
-use futures_util::future::join_all;
 use indicatif::MultiProgress;
-use rayon::ThreadPoolBuilder;
+use rayon::{
+    prelude::{IndexedParallelIterator, IntoParallelRefIterator, ParallelIterator},
+    ThreadPool, ThreadPoolBuilder,
+};
 use repo::Repository;
 use reqwest::{Client, Url};
-use scraper::{Html, Selector};
 use std::{
-    sync::{
-        atomic::{AtomicUsize, Ordering},
-        Arc, Mutex,
-    },
+    sync::{Arc, Mutex, OnceLock},
     time::Instant,
 };
-use tracing::error;
-use types::RepoName;
+use types::{RepoName, RepositoryMetadata};
 
+pub mod api;
+pub mod batch;
+pub mod bitbucket;
+pub mod blame;
 pub mod config;
+pub mod contributions;
+pub mod crawl;
+pub mod credentials;
+pub mod forge;
+pub mod gist;
+pub mod gitea;
+pub mod gitlab;
+pub mod graphql;
+pub mod html;
+pub mod listing;
 pub mod log;
+#[cfg(test)]
+mod mock;
 pub mod org;
+pub mod ownership;
 pub mod repo;
+pub mod selection;
+pub mod sourcehut;
+pub mod submodule;
+pub mod target;
+pub mod tls;
 pub mod types;
 pub mod user;
+pub mod whois;
 
-const NUMBER_OF_REPO_PER_PAGE: usize = 30;
-
-#[async_trait]
-pub trait Factory {
-    async fn _repositories_count(client: &Client, url: Url) -> usize;
-
-    fn _pages_count(repo_count: usize) -> usize {
-        let modulo = repo_count % NUMBER_OF_REPO_PER_PAGE;
-        if modulo.eq(&0) {
-            repo_count / NUMBER_OF_REPO_PER_PAGE
-        } else {
-            ((repo_count - modulo) / NUMBER_OF_REPO_PER_PAGE) + 1
-        }
-    }
-
-    fn _build_repo_links(page_url: Url, repo_count: usize, pages_count: usize) -> Vec<Url> {
-        let mut pages_urls = Vec::with_capacity(repo_count);
-        for i in 1..pages_count + 1 {
-            let url = format!("{}&page={}", page_url, i);
-            pages_urls.push(Url::parse(&url).unwrap());
-        }
-
-        pages_urls
-    }
-}
-
-#[async_trait]
-pub trait ExtractLog {
-    async fn common_log_feature(
-        &self,
-        client: &Client,
-        selector: Selector,
-    ) -> DashMap<RepoName, Repository, ahash::RandomState> {
-        let start_a = Instant::now();
-
-        let repo_count = self.get_repo_count();
-        let all_branches = self.get_all_branches();
-        let pages_urls = self.get_pages_url();
-        let url = self.get_url();
-
-        let (tx_url, rx_url) = bounded(repo_count);
-        let mut tokio_handles = Vec::with_capacity(pages_urls.len());
-        for page in pages_urls {
-            let client = client.clone();
-            let url = url.clone();
-            let tx_url = tx_url.clone();
-            let selector = selector.clone();
-
-            let handle = tokio::spawn(async move {
-                let resp = client.get(page).send().await.unwrap();
-                let text = resp.text().await.unwrap();
-                let parser = Html::parse_document(&text);
-
-                parser
-                    .select(&selector)
-                    .map(|link| {
-                        let endpoint_url = link.value().attr("href").unwrap().to_string();
-                        let repo_name = endpoint_url.split('/').last().unwrap();
-                        let repo_url = format!("{}{}/", url, repo_name);
-                        let sending = tx_url.send(Url::parse(&repo_url).unwrap());
-                        match sending {
-                            Ok(_) => tracing::debug!("Send url {}", repo_url),
-                            Err(e) => {
-                                error!("Failed to send {} with : [{:?}]", repo_url, e)
-                            }
-                        }
-                    })
-                    .for_each(drop);
-            });
-
-            tokio_handles.push(handle);
-        }
-        drop(tx_url);
-
-        let mut queue_handles = Vec::with_capacity(repo_count);
-        let (tx, rx) = bounded(repo_count);
-        let mpb: Arc<Mutex<MultiProgress>> = Arc::new(Mutex::new(MultiProgress::new()));
-
-        for i in 0..repo_count {
-            let tx = tx.clone();
-            let rx_url = rx_url.clone();
-            let mpb = mpb.clone();
-
-            let handle = rayon::spawn(move || {
-                let clonable_url = rx_url.recv().unwrap();
-                let repo_config = RepositoryConfig::new(clonable_url, all_branches);
-
-                let repo = RepositoryFactory::with_config(repo_config).create(mpb);
-
-                tx.send(repo).unwrap();
-                drop(tx);
-            });
-
-            queue_handles.push(handle)
-        }
-        drop(tx);
-        drop(rx_url);
+/// Pool cloning and extracting repositories, shared by all the targets of a run
+fn extraction_pool() -> &'static ThreadPool {
+    static POOL: OnceLock<ThreadPool> = OnceLock::new();
 
+    POOL.get_or_init(|| {
         tracing::info!("Number of threads : {}", rayon::current_num_threads());
-        let current_num_thread = rayon::current_num_threads() - 2;
-        let pool = ThreadPoolBuilder::new()
+        let current_num_thread = rayon::current_num_threads().saturating_sub(2).max(1);
+        ThreadPoolBuilder::new()
             .num_threads(current_num_thread)
             .build()
-            .unwrap();
-
-        let dash: Arc<DashMap<RepoName, Repository, RandomState>> = Arc::new(
-            DashMap::with_capacity_and_hasher(repo_count, RandomState::new()),
-        );
-        let atomic_count = Arc::new(AtomicUsize::new(0));
-
-        let dash_result: Arc<DashMap<RepoName, Repository, RandomState>> =
-            pool.scope(move |scope| {
-                for _ in 0..repo_count {
-                    let dash = dash.clone();
-                    let rx = rx.clone();
-                    let atomic_count = atomic_count.clone();
+            .unwrap()
+    })
+}
 
-                    scope.spawn(move |_| {
-                        let repo = rx.recv().unwrap();
-                        drop(rx);
+/// Clone and extract the logs of listed repositories, `repositories_metadata` is in the order of `repo_urls`.
+/// Repositories are keyed by their name relative to `root_owner`, see `Repository::relative_name`.
+pub fn extract_repositories(
+    repo_urls: &[Url],
+    repositories_metadata: &[RepositoryMetadata],
+    root_owner: &str,
+    all_branches: bool,
+    log_config: &LogConfig,
+) -> DashMap<RepoName, Repository, RandomState> {
+    extract_repositories_keyed(
+        repo_urls,
+        repositories_metadata,
+        all_branches,
+        log_config,
+        |repo| repo.relative_name(root_owner),
+    )
+}
 
-                        let data = repo.clone().extract_log();
-                        let repo_name_key = RepoName(repo.name.clone());
-                        dash.insert(repo_name_key, data);
-                        atomic_count.fetch_add(1, Ordering::Relaxed);
-                        //println!(
-                        //    "Repository {} handled : {}/{}",
-                        //    repo.name,
-                        //    atomic_count.load(Ordering::Relaxed),
-                        //    repo_count
-                        //);
-                    })
+/// Same as `extract_repositories`, repositories are keyed by `key`
+pub fn extract_repositories_keyed(
+    repo_urls: &[Url],
+    repositories_metadata: &[RepositoryMetadata],
+    all_branches: bool,
+    log_config: &LogConfig,
+    key: impl Fn(&Repository) -> String + Sync,
+) -> DashMap<RepoName, Repository, RandomState> {
+    let start = Instant::now();
+
+    let repositories = DashMap::with_capacity_and_hasher(repo_urls.len(), RandomState::new());
+    let mpb: Arc<Mutex<MultiProgress>> = Arc::new(Mutex::new(MultiProgress::new()));
+
+    extraction_pool().install(|| {
+        repo_urls.par_iter().enumerate().for_each(|(index, url)| {
+            let repo_config = RepositoryConfig::new(url.clone(), all_branches, log_config.clone());
+
+            match RepositoryFactory::with_config(repo_config).create(mpb.clone()) {
+                Ok(mut repo) => {
+                    if let Some(metadata) = repositories_metadata.get(index) {
+                        repo.set_listing_status(metadata);
+                    }
+                    let repo_name_key = RepoName(key(&repo));
+                    repositories.insert(repo_name_key, repo.extract_log());
                 }
-                dash
-            });
-        drop(pool);
-
-        join_all(tokio_handles).await;
-
-        tracing::info!(
-            "Fetching and Cloning handled in {:?} for {}",
-            start_a.elapsed(),
-            repo_count
-        );
-
-        Arc::try_unwrap(dash_result).unwrap()
-    }
+                Err(e) => ::log::warn!("Skip {} : {}", url, e.message()),
+            }
+        })
+    });
+
+    tracing::info!(
+        "Fetching and Cloning handled in {:?} for {}",
+        start.elapsed(),
+        repo_urls.len()
+    );
+
+    repositories
+}
 
+#[async_trait]
+pub trait ExtractLog {
     async fn extract_log(mut self, client: &Client) -> Self;
-
-    // Common Getter
-    fn get_repo_count(&self) -> usize;
-    fn get_all_branches(&self) -> bool;
-    fn get_url(&self) -> Url;
-    fn get_pages_url(&self) -> Vec<Url>;
 }
 
 pub struct Logger;
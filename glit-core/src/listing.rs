@@ -0,0 +1,70 @@
+use reqwest::Url;
+use serde::Serialize;
+
+use crate::{
+    forge::TargetKind, selection::SkippedRepository, target::Target, types::RepositoryMetadata,
+};
+
+/// Repository a scan would clone
+#[derive(Debug, Clone, Serialize)]
+pub struct ListedRepository {
+    pub clone_url: String,
+    #[serde(flatten)]
+    pub metadata: RepositoryMetadata,
+}
+
+/// What a scan would clone, built from the listing phase only
+#[derive(Debug, Clone, Serialize)]
+pub struct Listing {
+    pub owner: String,
+    pub kind: TargetKind,
+    pub repositories: Vec<ListedRepository>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub skipped_repositories: Vec<SkippedRepository>,
+}
+
+impl Listing {
+    /// `repositories_metadata` is in the order of `repo_urls`
+    pub fn new(
+        owner: &str,
+        kind: TargetKind,
+        repo_urls: &[Url],
+        repositories_metadata: &[RepositoryMetadata],
+        skipped_repositories: &[SkippedRepository],
+    ) -> Self {
+        let repositories = repo_urls
+            .iter()
+            .zip(repositories_metadata)
+            .map(|(url, metadata)| ListedRepository {
+                clone_url: url.to_string(),
+                metadata: metadata.clone(),
+            })
+            .collect();
+
+        Self {
+            owner: owner.to_string(),
+            kind,
+            repositories,
+            skipped_repositories: skipped_repositories.to_vec(),
+        }
+    }
+
+    /// Listing of a repository target, which is its own single repository
+    pub fn repository(clone_url: &Url) -> Self {
+        let target = Target::repository(clone_url.as_str())
+            .unwrap_or_else(|e| panic!("Invalid repository url : {}", e));
+        let metadata = RepositoryMetadata {
+            name: target.name.unwrap_or_default(),
+            url: clone_url.to_string(),
+            ..Default::default()
+        };
+
+        Self::new(
+            &target.owner,
+            TargetKind::Repository,
+            std::slice::from_ref(clone_url),
+            &[metadata],
+            &[],
+        )
+    }
+}
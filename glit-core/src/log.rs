@@ -1,12 +1,114 @@
-use crate::repo::Committers;
-use git2::{Oid, Sort};
+use crate::{config::LogConfig, ownership::Ownership, repo::Committers};
+use git2::{Diff, Oid, Sort};
+use globset::{Glob, GlobSet, GlobSetBuilder};
 use indicatif::{ProgressBar, ProgressStyle};
-use std::{path::PathBuf, thread};
+use std::{
+    cmp::Reverse,
+    collections::HashSet,
+    path::{Path, PathBuf},
+    thread,
+};
+
+/// Keep only commits whose diff against their first parent touches one of the given globs.
+/// An empty filter keeps every commit.
+#[derive(Debug, Clone, Default)]
+pub struct PathFilter {
+    patterns: Vec<String>,
+    glob_set: Option<GlobSet>,
+}
+
+impl PathFilter {
+    pub fn new(patterns: Vec<String>) -> Result<Self, globset::Error> {
+        if patterns.is_empty() {
+            return Ok(Self::default());
+        }
+
+        let mut builder = GlobSetBuilder::new();
+        for pattern in &patterns {
+            builder.add(Glob::new(pattern)?);
+        }
+
+        Ok(Self {
+            patterns,
+            glob_set: Some(builder.build()?),
+        })
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.glob_set.is_none()
+    }
+
+    pub fn patterns(&self) -> &[String] {
+        &self.patterns
+    }
+
+    pub fn matches(&self, path: &Path) -> bool {
+        match &self.glob_set {
+            Some(glob_set) => glob_set.is_match(path),
+            None => true,
+        }
+    }
+
+    pub fn touches(&self, diff: &Diff) -> bool {
+        diff.deltas().any(|delta| {
+            [delta.old_file().path(), delta.new_file().path()]
+                .into_iter()
+                .flatten()
+                .any(|path| self.matches(path))
+        })
+    }
+}
+
+/// Diff a commit against its first parent. Root commits are diffed against the empty tree.
+fn first_parent_diff<'repo>(repo: &'repo git2::Repository, commit_id: Oid) -> Diff<'repo> {
+    let commit = repo.find_commit(commit_id).unwrap();
+    let tree = commit.tree().unwrap();
+    let parent_tree = commit.parent(0).ok().map(|parent| parent.tree().unwrap());
+
+    repo.diff_tree_to_tree(parent_tree.as_ref(), Some(&tree), None)
+        .unwrap()
+}
 
 pub struct Log {}
 
 impl Log {
-    pub fn build(path: PathBuf, repo_name: String, branch: String) -> Committers {
+    /// The `max_commits` most recent commits of a repository, across the clones of all its branches
+    pub fn recent_commits(paths: &[PathBuf], max_commits: usize) -> HashSet<Oid> {
+        let mut commits = Vec::new();
+
+        for path in paths {
+            let repo = git2::Repository::open_bare(path.as_path()).unwrap();
+            let mut revwalk = repo.revwalk().unwrap();
+            revwalk.set_sorting(Sort::TIME).unwrap();
+            revwalk.push_head().unwrap();
+
+            // The most recent commits of the repository are among the most recent of their branch
+            for commit_id in revwalk.map(|id| id.unwrap()).take(max_commits) {
+                let time = repo.find_commit(commit_id).unwrap().time().seconds();
+                commits.push((time, commit_id));
+            }
+        }
+
+        commits.sort_by_key(|(time, commit_id)| (Reverse(*time), *commit_id));
+        commits.dedup_by_key(|(_, commit_id)| *commit_id);
+        commits
+            .into_iter()
+            .map(|(_, commit_id)| commit_id)
+            .take(max_commits)
+            .collect()
+    }
+
+    /// Walk the commits of a branch clone, only the `recent_commits` of the repository when given
+    pub fn build(
+        path: PathBuf,
+        repo_name: String,
+        branch: String,
+        log_config: &LogConfig,
+        recent_commits: Option<&HashSet<Oid>>,
+    ) -> Committers {
+        let path_filter = &log_config.path_filter;
+        let needs_diff = !path_filter.is_empty() || log_config.ownership;
+
         let repo = git2::Repository::open_bare(path.as_path()).unwrap();
         let mut revwalk = repo.revwalk().unwrap();
         revwalk.set_sorting(Sort::TIME).unwrap();
@@ -20,7 +122,11 @@ impl Log {
             &path
         );
 
-        let walk: Vec<Oid> = revwalk.map(|id| id.unwrap()).collect();
+        let walk: Vec<Oid> = revwalk
+            .map(|id| id.unwrap())
+            .filter(|id| recent_commits.is_none_or(|commits| commits.contains(id)))
+            .take(recent_commits.map_or(usize::MAX, HashSet::len))
+            .collect();
 
         let pb = ProgressBar::new(walk.len().try_into().unwrap());
         pb.set_message(format!("[{}][{}]", repo_name, branch));
@@ -34,7 +140,21 @@ impl Log {
 
         for (i, commit_id) in walk.into_iter().enumerate() {
             pb.set_position(i.try_into().unwrap());
+            if !needs_diff {
+                repo_data.update(&repo, commit_id);
+                continue;
+            }
+
+            let diff = first_parent_diff(&repo, commit_id);
+            if !path_filter.is_empty() && !path_filter.touches(&diff) {
+                continue;
+            }
+
             repo_data.update(&repo, commit_id);
+            if log_config.ownership {
+                let ownership = Ownership::from_diff(&diff, path_filter);
+                repo_data.add_ownership(&repo, commit_id, &ownership);
+            }
         }
 
         pb.finish_with_message(format!("[{} ✅][{} ✅]", repo_name, branch));
@@ -42,3 +162,59 @@ impl Log {
         repo_data
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PATCH: &[u8] = b"diff --git a/infra/terraform/main.tf b/infra/terraform/main.tf
+--- a/infra/terraform/main.tf
++++ b/infra/terraform/main.tf
+@@ -1 +1 @@
+-old
++new
+diff --git a/src/lib.rs b/src/lib.rs
+--- a/src/lib.rs
++++ b/src/lib.rs
+@@ -1 +1 @@
+-old
++new
+";
+
+    fn path_filter(patterns: &[&str]) -> PathFilter {
+        PathFilter::new(patterns.iter().map(|pattern| pattern.to_string()).collect()).unwrap()
+    }
+
+    #[test]
+    fn empty_path_filter_matches_everything() {
+        let path_filter = path_filter(&[]);
+
+        assert!(path_filter.is_empty());
+        assert!(path_filter.patterns().is_empty());
+        assert!(path_filter.matches(Path::new("any/file.rs")));
+    }
+
+    #[test]
+    fn path_filter_matches_globs() {
+        let path_filter = path_filter(&["infra/terraform/**", "*.md"]);
+
+        assert!(!path_filter.is_empty());
+        assert!(path_filter.matches(Path::new("infra/terraform/modules/vpc.tf")));
+        assert!(path_filter.matches(Path::new("docs/README.md")));
+        assert!(!path_filter.matches(Path::new("infra/ansible/site.yml")));
+    }
+
+    #[test]
+    fn path_filter_touches_diff() {
+        let diff = Diff::from_buffer(PATCH).unwrap();
+
+        assert!(path_filter(&["infra/terraform/**"]).touches(&diff));
+        assert!(path_filter(&["src/*.rs"]).touches(&diff));
+        assert!(!path_filter(&["docs/**"]).touches(&diff));
+    }
+
+    #[test]
+    fn invalid_glob_is_rejected() {
+        assert!(PathFilter::new(vec!["infra/[".to_string()]).is_err());
+    }
+}
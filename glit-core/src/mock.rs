@@ -0,0 +1,72 @@
+//! Local HTTP server answering canned JSON pages, to test the API clients through `--api-url`
+
+use reqwest::Url;
+use std::{
+    io::{BufRead, BufReader, Write},
+    net::TcpListener,
+    thread::{self, JoinHandle},
+};
+
+pub(crate) struct MockPage {
+    pub body: String,
+    /// Path of the next page, sent in a `Link: <...>; rel="next"` header
+    pub next: Option<String>,
+}
+
+impl MockPage {
+    pub fn new(body: &str, next: Option<&str>) -> Self {
+        Self {
+            body: body.to_string(),
+            next: next.map(str::to_string),
+        }
+    }
+}
+
+/// Answer one request per page, in order, on a random port.
+/// Returns the base url of the server and a handle giving the request heads once every page is served.
+pub(crate) fn serve(pages: Vec<MockPage>) -> (Url, JoinHandle<Vec<String>>) {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let base_url = Url::parse(&format!(
+        "http://127.0.0.1:{}/",
+        listener.local_addr().unwrap().port()
+    ))
+    .unwrap();
+    let link_base = base_url.clone();
+
+    let server = thread::spawn(move || {
+        let mut requests = Vec::new();
+        for (page, stream) in pages.into_iter().zip(listener.incoming()) {
+            let mut stream = stream.unwrap();
+            let mut head = String::new();
+            let mut reader = BufReader::new(&stream);
+            loop {
+                let mut line = String::new();
+                reader.read_line(&mut line).unwrap();
+                if line.trim().is_empty() {
+                    break;
+                }
+                head.push_str(&line);
+            }
+            requests.push(head);
+
+            let link = match &page.next {
+                Some(next) => format!(
+                    "Link: <{}>; rel=\"next\"\r\n",
+                    link_base.join(next).unwrap()
+                ),
+                None => String::new(),
+            };
+            write!(
+                stream,
+                "HTTP/1.1 200 OK\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n{}\r\n{}",
+                page.body.len(),
+                link,
+                page.body
+            )
+            .unwrap();
+        }
+        requests
+    });
+
+    (base_url, server)
+}
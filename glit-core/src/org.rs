@@ -2,10 +2,21 @@ use ahash::RandomState;
 use async_trait::async_trait;
 use dashmap::DashMap;
 use reqwest::{Client, Url};
-use scraper::{Html, Selector};
 use serde::Serialize;
-
-use crate::{config::OrgConfig, repo::Repository, types::RepoName, ExtractLog, Factory};
+use std::collections::BTreeMap;
+
+use crate::{
+    config::{ApiConfig, Backend, ForgeKind, ListingFilter, LogConfig, OrgConfig, UserConfig},
+    extract_repositories,
+    forge::{forge_for, ForgeError, OwnerKind, TargetKind},
+    listing::Listing,
+    repo::Repository,
+    selection::{RepositorySelection, SkippedRepository},
+    target::Target,
+    types::{RepoName, RepositoryMetadata},
+    user::{User, UserFactory},
+    ExtractLog,
+};
 
 #[derive(Debug, Clone, Serialize)]
 pub struct Org {
@@ -14,17 +25,38 @@ pub struct Org {
     pub url: Url,
     pub repo_count: usize,
     #[serde(skip)]
-    pub pages_urls: Vec<Url>,
+    pub repo_urls: Vec<Url>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub repositories_metadata: Vec<RepositoryMetadata>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub skipped_repositories: Vec<SkippedRepository>,
     #[serde(skip)]
     pub all_branches: bool,
+    #[serde(skip)]
+    pub log_config: LogConfig,
     pub repositories_data: DashMap<RepoName, Repository, RandomState>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub members: Vec<String>,
+    /// Reports of the members scanned as users, keyed by login
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    pub members_data: BTreeMap<String, User>,
+    /// Options of the member scans, `None` when members are not expanded
+    #[serde(skip)]
+    member_config: Option<UserConfig>,
 }
 
 pub struct OrgFactory {
     url: Url,
     name: String,
-    page_url: Url,
     all_branches: bool,
+    log_config: LogConfig,
+    forge: ForgeKind,
+    backend: Backend,
+    listing_filter: ListingFilter,
+    selection: RepositorySelection,
+    api_config: ApiConfig,
+    members: bool,
+    expand_members: bool,
 }
 
 impl OrgFactory {
@@ -32,97 +64,154 @@ impl OrgFactory {
         // CLI param
         let url = org_config.url;
         let all_branches = org_config.all_branches;
+        let log_config = org_config.log_config;
+        let forge = org_config.forge;
+        let backend = org_config.backend;
+        let api_config = org_config.api_config;
+        let listing_filter = org_config.listing_filter;
+        let selection = org_config.selection;
+        let members = org_config.members || org_config.expand_members;
+        let expand_members = org_config.expand_members;
 
         // Craft other param
-        let mut path_segment = url.path_segments().unwrap();
-        let name = path_segment.next().unwrap().to_string();
-
-        let host = url.host().unwrap().to_string();
-        let scheme = url.scheme();
-
-        let page_url = format!(
-            "{}://{}/orgs/{}/repositories?q=&type=source",
-            scheme, host, name
-        );
-        let page_url = Url::parse(&page_url).unwrap();
+        let target =
+            Target::owner(url.as_str()).unwrap_or_else(|e| panic!("Invalid org url : {}", e));
+        let name = target.owner;
 
         Self {
             url,
             name,
-            page_url,
             all_branches,
+            log_config,
+            forge,
+            backend,
+            api_config,
+            listing_filter,
+            selection,
+            members,
+            expand_members,
         }
     }
 
+    /// Panics when the repositories of the organization can not be listed
     pub async fn build_with_client(self, client: &Client) -> Org {
-        let repo_count = Self::_repositories_count(client, self.page_url.clone()).await;
-        let pages_count = Self::_pages_count(repo_count);
-        let pages_urls = Self::_build_repo_links(self.page_url, repo_count, pages_count);
+        let name = self.name.clone();
+        self.try_build_with_client(client)
+            .await
+            .unwrap_or_else(|e| panic!("Failed to list repositories of {} : {}", name, e))
+    }
 
-        Org {
+    /// List the repositories of the organization, and its members when asked.
+    /// Failing to list members is only logged.
+    pub async fn try_build_with_client(self, client: &Client) -> Result<Org, ForgeError> {
+        let (repositories, members) = {
+            let forge = forge_for(
+                self.forge,
+                self.backend,
+                client,
+                &self.api_config,
+                &self.url,
+                self.listing_filter,
+            );
+            let listed = forge.list_repositories(OwnerKind::Org, &self.name).await?;
+            let repositories =
+                self.selection
+                    .select_listed(forge.as_ref(), listed, &self.listing_filter);
+
+            let members = match self.members {
+                true => forge.list_members(&self.name).await.unwrap_or_else(|e| {
+                    log::warn!("Failed to list members of {} : {}", self.name, e);
+                    Vec::new()
+                }),
+                false => Vec::new(),
+            };
+
+            (repositories, members)
+        };
+        let repo_count = repositories.urls.len();
+
+        let member_config = self.expand_members.then(|| UserConfig {
+            url: self.url.clone(),
+            all_branches: self.all_branches,
+            log_config: self.log_config.clone(),
+            gists: false,
+            forge: self.forge,
+            backend: self.backend,
+            api_config: self.api_config.clone(),
+            listing_filter: self.listing_filter,
+            selection: self.selection.clone(),
+            contributions: false,
+        });
+
+        Ok(Org {
             name: self.name,
             url: self.url,
             repo_count,
-            pages_urls,
+            repo_urls: repositories.urls,
+            repositories_metadata: repositories.metadata,
+            skipped_repositories: repositories.skipped,
             all_branches: self.all_branches,
+            log_config: self.log_config,
             repositories_data: DashMap::<_, _, RandomState>::with_capacity_and_hasher(
                 repo_count,
                 RandomState::new(),
             ),
-        }
+            members,
+            members_data: BTreeMap::new(),
+            member_config,
+        })
     }
 }
 
-#[async_trait]
-impl Factory for OrgFactory {
-    async fn _repositories_count(client: &Client, url: Url) -> usize {
-        let resp = client.get(url).send().await.unwrap();
-        let text = resp.text().await.unwrap();
-
-        let parser = Html::parse_document(&text);
-        let selector_repositories_count =
-            Selector::parse(r#"main > div > div > div > div > div > div > div > span > strong"#)
-                .unwrap();
-
-        let repository_count_str = parser
-            .select(&selector_repositories_count)
-            .next()
-            .unwrap()
-            .inner_html();
-
-        repository_count_str
-            .trim()
-            .replace(',', "")
-            .parse::<usize>()
-            .unwrap()
+impl Org {
+    /// Scan the repositories of each member as a user, a member whose listing fails is skipped
+    async fn extract_members_log(&mut self, client: &Client, member_config: UserConfig) {
+        for login in &self.members {
+            let user_config = UserConfig {
+                url: self.url.join(&format!("/{}/", login)).unwrap(),
+                ..member_config.clone()
+            };
+
+            match UserFactory::with_config(user_config)
+                .try_build_with_client(client)
+                .await
+            {
+                Ok(user) => {
+                    let user = user.extract_log(client).await;
+                    self.members_data.insert(login.clone(), user);
+                }
+                Err(e) => log::warn!("Skip member {} : {}", login, e),
+            }
+        }
+    }
+
+    /// Repositories that would be cloned, without cloning them
+    pub fn listing(&self) -> Listing {
+        Listing::new(
+            &self.name,
+            TargetKind::Org,
+            &self.repo_urls,
+            &self.repositories_metadata,
+            &self.skipped_repositories,
+        )
     }
 }
 
 #[async_trait]
 impl ExtractLog for Org {
     async fn extract_log(mut self, client: &Client) -> Self {
-        let org_selector = Selector::parse(
-            r#"main > div > div > div > div > div > div > ul > li > div > div > div > h3 > a"#,
-        )
-        .unwrap();
-
-        self.repositories_data = Self::common_log_feature(&self, client, org_selector).await;
-        self
-    }
-
-    fn get_all_branches(&self) -> bool {
-        self.all_branches
-    }
-
-    fn get_url(&self) -> Url {
-        self.url.clone()
-    }
+        self.repositories_data = extract_repositories(
+            &self.repo_urls,
+            &self.repositories_metadata,
+            &self.name,
+            self.all_branches,
+            &self.log_config,
+        );
 
-    fn get_repo_count(&self) -> usize {
-        self.repo_count
-    }
+        if let Some(member_config) = self.member_config.take() {
+            self.extract_members_log(client, member_config).await;
+        }
 
-    fn get_pages_url(&self) -> Vec<Url> {
-        self.pages_urls.clone()
+        self
     }
 }
@@ -0,0 +1,84 @@
+use crate::log::PathFilter;
+use git2::{Diff, Patch};
+use serde::{Deserialize, Serialize};
+use std::{collections::BTreeMap, path::Path};
+
+const ROOT_DIRECTORY: &str = ".";
+const NO_EXTENSION: &str = "none";
+
+/// What an identity works on: touched directories and file extensions, with line churn.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct Ownership {
+    pub directories: BTreeMap<String, usize>,
+    pub extensions: BTreeMap<String, usize>,
+    pub lines_added: usize,
+    pub lines_removed: usize,
+}
+
+impl Ownership {
+    pub fn from_diff(diff: &Diff, path_filter: &PathFilter) -> Self {
+        let mut ownership = Self::default();
+
+        for (idx, delta) in diff.deltas().enumerate() {
+            let path = match delta.new_file().path().or(delta.old_file().path()) {
+                Some(path) => path,
+                None => continue,
+            };
+
+            if !path_filter.matches(path) {
+                continue;
+            }
+
+            *ownership.directories.entry(directory_of(path)).or_insert(0) += 1;
+            *ownership.extensions.entry(extension_of(path)).or_insert(0) += 1;
+
+            if let Ok(Some(patch)) = Patch::from_diff(diff, idx) {
+                if let Ok((_, additions, deletions)) = patch.line_stats() {
+                    ownership.lines_added += additions;
+                    ownership.lines_removed += deletions;
+                }
+            }
+        }
+
+        ownership
+    }
+
+    pub fn merge(&mut self, other: &Ownership) {
+        for (directory, count) in &other.directories {
+            *self.directories.entry(directory.clone()).or_insert(0) += count;
+        }
+        for (extension, count) in &other.extensions {
+            *self.extensions.entry(extension.clone()).or_insert(0) += count;
+        }
+        self.lines_added += other.lines_added;
+        self.lines_removed += other.lines_removed;
+    }
+
+    pub fn top_directories(&self, limit: usize) -> Vec<(&String, &usize)> {
+        top(&self.directories, limit)
+    }
+
+    pub fn top_extensions(&self, limit: usize) -> Vec<(&String, &usize)> {
+        top(&self.extensions, limit)
+    }
+}
+
+fn directory_of(path: &Path) -> String {
+    match path.parent() {
+        Some(parent) if !parent.as_os_str().is_empty() => parent.to_string_lossy().to_string(),
+        _ => ROOT_DIRECTORY.to_string(),
+    }
+}
+
+fn extension_of(path: &Path) -> String {
+    path.extension()
+        .map(|extension| extension.to_string_lossy().to_string())
+        .unwrap_or_else(|| NO_EXTENSION.to_string())
+}
+
+fn top(counts: &BTreeMap<String, usize>, limit: usize) -> Vec<(&String, &usize)> {
+    let mut sorted = counts.iter().collect::<Vec<_>>();
+    sorted.sort_by(|a, b| b.1.cmp(a.1));
+    sorted.truncate(limit);
+    sorted
+}
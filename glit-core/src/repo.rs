@@ -1,7 +1,12 @@
 use crate::{
-    config::RepositoryConfig,
+    blame::Blame,
+    config::{LogConfig, RepositoryConfig},
+    credentials::Credentials,
     log::Log,
-    types::{AuthorName, BranchName},
+    ownership::Ownership,
+    submodule::{scan_submodules, visited_key},
+    target::Target,
+    types::{AuthorName, BranchName, RepositoryMetadata},
 };
 use ahash::{HashMap, HashMapExt};
 use git2::{build::RepoBuilder, BranchType, Oid};
@@ -14,7 +19,7 @@ use rayon::prelude::{IntoParallelRefIterator, ParallelIterator};
 use reqwest::Url;
 use serde::{Deserialize, Serialize};
 use std::{
-    collections::BTreeMap,
+    collections::{BTreeMap, HashSet},
     fs::remove_dir_all,
     path::{Path, PathBuf},
     str::FromStr,
@@ -31,13 +36,42 @@ pub struct Repository {
     branches: Vec<BranchName>,
     #[serde(skip)]
     clone_paths: Vec<PathBuf>,
+    #[serde(skip)]
+    url: Option<Url>,
+    #[serde(skip)]
+    all_branches: bool,
+    #[serde(skip)]
+    log_config: LogConfig,
+    #[serde(skip)]
+    visited_submodules: Arc<Mutex<HashSet<String>>>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub paths: Vec<String>,
     pub branch_data: HashMap<BranchName, Committers>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub blame: Option<Blame>,
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub submodules: HashMap<String, Repository>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub wiki: Option<Box<Repository>>,
+    /// Status known from the listing of a user or an organization
+    #[serde(default, skip_serializing_if = "is_false")]
+    pub fork: bool,
+    #[serde(default, skip_serializing_if = "is_false")]
+    pub archived: bool,
+    #[serde(default, skip_serializing_if = "is_false")]
+    pub mirror: bool,
+}
+
+pub(crate) fn is_false(value: &bool) -> bool {
+    !value
 }
 
 pub struct RepositoryFactory {
     all_branches: bool,
     branches: Vec<BranchName>,
     url: Url,
+    log_config: LogConfig,
+    clone_failure_level: log::Level,
     //mpb: Arc<Mutex<MultiProgress>>,
 }
 
@@ -45,6 +79,7 @@ impl RepositoryFactory {
     pub fn with_config(repository_config: RepositoryConfig) -> Self {
         let url = repository_config.url;
         let all_branches: bool = repository_config.all_branches;
+        let log_config = repository_config.log_config;
 
         //let mpb = Arc::new(Mutex::new(MultiProgress::new()));
 
@@ -52,10 +87,18 @@ impl RepositoryFactory {
             all_branches,
             url,
             branches: Vec::<BranchName>::new(),
+            log_config,
+            clone_failure_level: log::Level::Error,
             //mpb,
         }
     }
 
+    /// Log level of a failed clone, lower it for probes that are expected to fail like missing wikis
+    pub fn clone_failure_level(mut self, level: log::Level) -> Self {
+        self.clone_failure_level = level;
+        self
+    }
+
     fn get_head_branch(repo: &git2::Repository) -> String {
         let head = repo.head();
         if let Ok(head_ref) = head {
@@ -63,7 +106,7 @@ impl RepositoryFactory {
                 .name()
                 .unwrap()
                 .split('/')
-                .last()
+                .next_back()
                 .unwrap()
                 .to_string()
         } else {
@@ -100,6 +143,8 @@ impl RepositoryFactory {
         url: &Url,
         repo_name: String,
         path: &Path,
+        credentials: &Credentials,
+        failure_level: log::Level,
         //mpb: Arc<Mutex<MultiProgress>>,
     ) -> Result<git2::Repository, git2::Error> {
         let pb_clone = ProgressBar::new(0);
@@ -112,16 +157,17 @@ impl RepositoryFactory {
         )
         .unwrap()
         .progress_chars("#>-");
-        
+
         let style_delta = ProgressStyle::with_template(
             "🚀 RESOLVING  {msg}[{elapsed_precise}] [{wide_bar:.cyan/blue}] {human_pos}/{human_len} ",
         )
         .unwrap()
         .progress_chars("#>-");
-        
+
         pb_clone.set_style(style_clone);
         pb_delta.set_style(style_delta);
-        let cb = create_multi_callback(repo_name, "default".to_string(), pb_clone, pb_delta ); //  , mpb
+        let mut cb = create_multi_callback(repo_name, "default".to_string(), pb_clone, pb_delta); //  , mpb
+        credentials.register(&mut cb);
 
         let mut fo = FetchOptions::new();
         fo.remote_callbacks(cb);
@@ -131,10 +177,10 @@ impl RepositoryFactory {
             .fetch_options(fo)
             .clone(url.as_str(), path);
 
-        match repo {
+        match &repo {
             Ok(_) => log::debug!("Cloning repo at {:?}", path.to_str().unwrap()),
-            Err(_) => {
-                log::error!("Failed to clone")
+            Err(e) => {
+                log::log!(failure_level, "Failed to clone {} : {}", url, e.message())
             }
         }
 
@@ -145,6 +191,7 @@ impl RepositoryFactory {
         url: Url,
         repo_name: String,
         branches: Vec<BranchName>,
+        credentials: &Credentials,
         //mpb: Arc<Mutex<MultiProgress>>,
     ) -> Vec<PathBuf> {
         let repo_name = repo_name.replace('-', "_");
@@ -159,7 +206,7 @@ impl RepositoryFactory {
                     "{}/{}/{}",
                     DEFAULT_PATH,
                     hashed_repo_name,
-                    branch.to_string(),
+                    branch,
                 );
 
                 let branch_clone_path = PathBuf::from_str(&path).unwrap();
@@ -172,21 +219,22 @@ impl RepositoryFactory {
                 )
                 .unwrap()
                 .progress_chars("#>-");
-                
+
                 let style_delta = ProgressStyle::with_template(
                     "🚀 RESOLVING  {msg}[{elapsed_precise}] [{wide_bar:.cyan/blue}] {human_pos}/{human_len} ",
                 )
                 .unwrap()
                 .progress_chars("#>-");
-                
+
                 pb_clone.set_style(style_clone);
                 pb_delta.set_style(style_delta);
-                let cb = create_multi_callback(
+                let mut cb = create_multi_callback(
                     repo_name.clone(),
                     branch.to_string(),
                     pb_clone,
                     pb_delta,
                 ); //,mpb
+                credentials.register(&mut cb);
 
                 let mut fo = FetchOptions::new();
                 fo.remote_callbacks(cb);
@@ -214,10 +262,13 @@ impl RepositoryFactory {
             .collect::<Vec<PathBuf>>()
     }
 
-    pub fn create(mut self, mpb: Arc<Mutex<MultiProgress>>) -> Repository {
-        let mut path_segments = self.url.path_segments().unwrap();
-        let owner = path_segments.next().unwrap().to_string();
-        let repo_name = path_segments.next().unwrap().to_string();
+    /// Clone the repository, an invalid url or a failed clone is returned as an error
+    pub fn create(mut self, _mpb: Arc<Mutex<MultiProgress>>) -> Result<Repository, git2::Error> {
+        let target = Target::repository(self.url.as_str())
+            .map_err(|e| git2::Error::from_str(&e.to_string()))?;
+        let owner = target.owner;
+        let repo_name = target.name.unwrap_or_default();
+        self.url = target.clone_url;
 
         // default location
         let hash_suffix = Alphanumeric.sample_string(&mut rand::thread_rng(), 6);
@@ -233,9 +284,10 @@ impl RepositoryFactory {
             &self.url,
             repo_name.clone(),
             clone_location.as_path(),
+            &self.log_config.credentials,
+            self.clone_failure_level,
             //mpb,
-        )
-        .unwrap();
+        )?;
 
         let head = Self::get_head_branch(&repo);
         if !head.is_empty() {
@@ -249,10 +301,12 @@ impl RepositoryFactory {
                 self.url.clone(),
                 repo_name.clone(),
                 branches.clone(),
+                &self.log_config.credentials,
                 //self.mpb,
             );
 
-            branches.push(BranchName(head));
+            // Default branch first, to match the order of clone_paths
+            branches.insert(0, BranchName(head));
             self.branches = branches.clone();
 
             clone_paths.extend(paths);
@@ -262,18 +316,93 @@ impl RepositoryFactory {
             self.branches = vec![BranchName(head)];
         }
 
-        Repository {
+        Ok(Repository {
             name: repo_name,
             owner,
             branches: self.branches.clone(),
             clone_paths,
+            url: Some(self.url),
+            all_branches: self.all_branches,
+            paths: self.log_config.path_filter.patterns().to_vec(),
+            log_config: self.log_config,
+            visited_submodules: Arc::new(Mutex::new(HashSet::new())),
             branch_data: HashMap::new(),
-        }
+            blame: None,
+            submodules: HashMap::new(),
+            wiki: None,
+            fork: false,
+            archived: false,
+            mirror: false,
+        })
     }
 }
 
 impl Repository {
+    /// Host the repository was cloned from, `None` for local repositories
+    pub fn host(&self) -> Option<&str> {
+        self.url.as_ref().and_then(|url| url.host_str())
+    }
+
+    /// Name of the repository relative to a user or an organization : `subgroup/name`
+    /// for repositories of nested gitlab subgroups, `name` otherwise
+    pub fn relative_name(&self, root_owner: &str) -> String {
+        match self.owner.strip_prefix(&format!("{}/", root_owner)) {
+            Some(subgroup) => format!("{}/{}", subgroup, self.name),
+            None => self.name.clone(),
+        }
+    }
+
+    /// Record the fork, archived and mirror status given by the listing
+    pub fn set_listing_status(&mut self, metadata: &RepositoryMetadata) {
+        self.fork = metadata.fork;
+        self.archived = metadata.archived;
+        self.mirror = metadata.mirror;
+    }
+
+    /// Share the set of already scanned submodule urls with a parent repository
+    pub fn with_visited_submodules(mut self, visited: Arc<Mutex<HashSet<String>>>) -> Self {
+        self.visited_submodules = visited;
+        self
+    }
+
     pub fn extract_log(mut self) -> Repository {
+        // Submodules are read from the default branch clone before clone folders get cleaned
+        if self.log_config.submodule_depth > 0 {
+            if let (Some(default_path), Some(url)) = (self.clone_paths.first(), &self.url) {
+                self.visited_submodules
+                    .lock()
+                    .unwrap()
+                    .insert(visited_key(url));
+
+                self.submodules = scan_submodules(
+                    default_path,
+                    url,
+                    self.all_branches,
+                    &self.log_config,
+                    self.visited_submodules.clone(),
+                );
+            }
+        }
+
+        // Blame the default branch clone before clone folders get cleaned
+        if self.log_config.blame {
+            if let Some(default_path) = self.clone_paths.first() {
+                let t1 = Instant::now();
+                self.blame = Some(Blame::build(
+                    default_path,
+                    self.name.clone(),
+                    &self.log_config.path_filter,
+                ));
+                log::info!("Blame Time : {:?}", t1.elapsed());
+            }
+        }
+
+        // The commit limit applies to the whole repository, not to each branch
+        let recent_commits = self
+            .log_config
+            .max_commits
+            .map(|max_commits| Log::recent_commits(&self.clone_paths, max_commits));
+
         self.branch_data = self
             .branches
             .clone()
@@ -282,8 +411,13 @@ impl Repository {
             .map(|(br, pt)| {
                 let t1 = Instant::now();
 
-                let repo_data: Committers =
-                    Log::build(pt.clone(), self.name.clone(), br.to_string());
+                let repo_data: Committers = Log::build(
+                    pt.clone(),
+                    self.name.clone(),
+                    br.to_string(),
+                    &self.log_config,
+                    recent_commits.as_ref(),
+                );
 
                 log::info!("Build log Time : {:?}", t1.elapsed());
 
@@ -296,10 +430,54 @@ impl Repository {
 
                 (br, repo_data)
             })
+            // Under the limit, a branch holding none of the recent commits is left out of the report
+            .filter(|(_, repo_data)| recent_commits.is_none() || !repo_data.committers.is_empty())
             .collect::<HashMap<_, _>>();
 
+        if self.log_config.wiki {
+            self.wiki = self.extract_wiki().map(Box::new);
+        }
+
         self
     }
+
+    /// Github wikis live in a separate `<repo>.wiki` git repository
+    fn wiki_url(&self) -> Option<Url> {
+        let mut wiki_url = self.url.clone()?;
+        let path = format!(
+            "{}.wiki/",
+            wiki_url
+                .path()
+                .trim_end_matches('/')
+                .trim_end_matches(".git")
+        );
+        wiki_url.set_path(&path);
+        Some(wiki_url)
+    }
+
+    fn extract_wiki(&self) -> Option<Repository> {
+        let wiki_url = self.wiki_url()?;
+        let log_config = LogConfig {
+            wiki: false,
+            submodule_depth: 0,
+            ..self.log_config.clone()
+        };
+
+        let repository_config = RepositoryConfig::new(wiki_url.clone(), false, log_config);
+        let mpb = Arc::new(Mutex::new(MultiProgress::new()));
+
+        // A missing wiki fails to clone, it is not an error
+        match RepositoryFactory::with_config(repository_config)
+            .clone_failure_level(log::Level::Debug)
+            .create(mpb)
+        {
+            Ok(wiki) => Some(wiki.extract_log()),
+            Err(e) => {
+                log::debug!("No wiki found at {} : {:?}", wiki_url, e);
+                None
+            }
+        }
+    }
 }
 
 type Mail = String;
@@ -307,6 +485,8 @@ type Mail = String;
 #[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub struct Committer {
     pub mails: BTreeMap<Mail, Vec<Mail>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub ownership: Option<Ownership>,
 }
 
 impl Committer {
@@ -316,6 +496,7 @@ impl Committer {
 
         Self {
             mails: commits_for_mail,
+            ownership: None,
         }
     }
 }
@@ -391,6 +572,25 @@ impl Committers {
 
         self
     }
+
+    pub fn add_ownership(
+        &mut self,
+        repo: &git2::Repository,
+        commit_id: Oid,
+        ownership: &Ownership,
+    ) -> &Self {
+        let commit = repo.find_commit(commit_id).unwrap();
+        let author: AuthorName = AuthorName(commit.author().name().unwrap_or("").to_string());
+
+        if let Some(committer) = self.committers.get_mut(&author) {
+            committer
+                .ownership
+                .get_or_insert_with(Ownership::default)
+                .merge(ownership);
+        }
+
+        self
+    }
 }
 
 fn create_multi_callback(
@@ -0,0 +1,345 @@
+use globset::{Glob, GlobMatcher};
+use regex::Regex;
+use reqwest::Url;
+use serde::Serialize;
+use std::fmt;
+
+use crate::{config::ListingFilter, forge::Forge, types::RepositoryMetadata};
+
+/// Patterns starting with `re:` are regular expressions, the others are globs
+const REGEX_PREFIX: &str = "re:";
+
+#[derive(Debug)]
+pub enum SelectionError {
+    Glob(globset::Error),
+    Regex(regex::Error),
+}
+
+impl fmt::Display for SelectionError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SelectionError::Glob(e) => write!(f, "invalid glob : {}", e),
+            SelectionError::Regex(e) => write!(f, "invalid regex : {}", e),
+        }
+    }
+}
+
+impl From<globset::Error> for SelectionError {
+    fn from(e: globset::Error) -> Self {
+        SelectionError::Glob(e)
+    }
+}
+
+impl From<regex::Error> for SelectionError {
+    fn from(e: regex::Error) -> Self {
+        SelectionError::Regex(e)
+    }
+}
+
+/// Repository name pattern
+#[derive(Debug, Clone)]
+enum NamePattern {
+    Glob(GlobMatcher),
+    Regex(Regex),
+}
+
+impl NamePattern {
+    fn parse(pattern: &str) -> Result<Self, SelectionError> {
+        match pattern.strip_prefix(REGEX_PREFIX) {
+            Some(regex) => Ok(NamePattern::Regex(Regex::new(regex)?)),
+            None => Ok(NamePattern::Glob(Glob::new(pattern)?.compile_matcher())),
+        }
+    }
+
+    fn matches(&self, name: &str) -> bool {
+        match self {
+            NamePattern::Glob(glob) => glob.is_match(name),
+            NamePattern::Regex(regex) => regex.is_match(name),
+        }
+    }
+}
+
+/// Listed repository that is not cloned
+#[derive(Debug, Clone, Serialize)]
+pub struct SkippedRepository {
+    pub name: String,
+    pub url: String,
+    pub reason: String,
+}
+
+impl SkippedRepository {
+    fn new(repository: RepositoryMetadata, reason: String) -> Self {
+        Self {
+            name: repository.name,
+            url: repository.url,
+            reason,
+        }
+    }
+}
+
+/// Repositories kept by a selection with their clone urls, and the skipped ones
+#[derive(Debug, Default)]
+pub(crate) struct SelectedRepositories {
+    pub metadata: Vec<RepositoryMetadata>,
+    pub urls: Vec<Url>,
+    pub skipped: Vec<SkippedRepository>,
+}
+
+/// Which listed repositories of a user or an organization are cloned.
+/// Every other repository is reported as skipped with the reason.
+#[derive(Debug, Clone, Default)]
+pub struct RepositorySelection {
+    include: Vec<NamePattern>,
+    exclude: Vec<NamePattern>,
+    pub max_repos: Option<usize>,
+    pub max_repo_size_kb: Option<u64>,
+}
+
+impl RepositorySelection {
+    pub fn new(
+        include: &[String],
+        exclude: &[String],
+        max_repos: Option<usize>,
+        max_repo_size_kb: Option<u64>,
+    ) -> Result<Self, SelectionError> {
+        Ok(Self {
+            include: include
+                .iter()
+                .map(|pattern| NamePattern::parse(pattern))
+                .collect::<Result<_, _>>()?,
+            exclude: exclude
+                .iter()
+                .map(|pattern| NamePattern::parse(pattern))
+                .collect::<Result<_, _>>()?,
+            max_repos,
+            max_repo_size_kb,
+        })
+    }
+
+    /// Why a repository is skipped, `None` when it is cloned
+    fn skip_reason(
+        &self,
+        repository: &RepositoryMetadata,
+        listing_filter: &ListingFilter,
+    ) -> Option<String> {
+        if !listing_filter.keeps(repository) {
+            let reason = match (repository.fork, repository.archived) {
+                (true, _) if !listing_filter.include_forks => "fork",
+                (_, true) if listing_filter.exclude_archived => "archived",
+                _ => "not a mirror",
+            };
+            return Some(reason.to_string());
+        }
+
+        if !self.include.is_empty()
+            && !self
+                .include
+                .iter()
+                .any(|pattern| pattern.matches(&repository.name))
+        {
+            return Some("name not included".to_string());
+        }
+
+        if self
+            .exclude
+            .iter()
+            .any(|pattern| pattern.matches(&repository.name))
+        {
+            return Some("name excluded".to_string());
+        }
+
+        // Repositories of unknown size are kept
+        match (repository.size_kb, self.max_repo_size_kb) {
+            (Some(size), Some(max_size)) if size > max_size => Some(format!(
+                "size of {} KB over the limit of {} KB",
+                size, max_size
+            )),
+            _ => None,
+        }
+    }
+
+    /// Split listed repositories between the ones to clone, in listing order, and the skipped ones
+    pub fn select(
+        &self,
+        repositories: Vec<RepositoryMetadata>,
+        listing_filter: &ListingFilter,
+    ) -> (Vec<RepositoryMetadata>, Vec<SkippedRepository>) {
+        let mut selected = Vec::new();
+        let mut skipped = Vec::new();
+
+        for repository in repositories {
+            let over_limit = self.max_repos.filter(|max| selected.len() >= *max);
+            let reason = self.skip_reason(&repository, listing_filter).or_else(|| {
+                over_limit.map(|max| format!("over the limit of {} repositories", max))
+            });
+
+            match reason {
+                Some(reason) => skipped.push(SkippedRepository::new(repository, reason)),
+                None => selected.push(repository),
+            }
+        }
+
+        if !skipped.is_empty() {
+            log::info!(
+                "Selected {} repositories, skipped {}",
+                selected.len(),
+                skipped.len()
+            );
+        }
+        (selected, skipped)
+    }
+
+    /// Same split for repositories listed by `forge`, which gives the clone urls of the kept ones
+    pub(crate) fn select_listed(
+        &self,
+        forge: &dyn Forge,
+        repositories: Vec<RepositoryMetadata>,
+        listing_filter: &ListingFilter,
+    ) -> SelectedRepositories {
+        let (metadata, skipped) = self.select(repositories, listing_filter);
+        let urls = metadata
+            .iter()
+            .map(|repository| forge.clone_url(repository))
+            .collect();
+
+        SelectedRepositories {
+            metadata,
+            urls,
+            skipped,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn repository(name: &str) -> RepositoryMetadata {
+        RepositoryMetadata {
+            name: name.to_string(),
+            url: format!("https://github.com/acme/{}", name),
+            ..RepositoryMetadata::default()
+        }
+    }
+
+    fn names(repositories: &[RepositoryMetadata]) -> Vec<&str> {
+        repositories
+            .iter()
+            .map(|repository| repository.name.as_str())
+            .collect()
+    }
+
+    #[test]
+    fn select_keeps_everything_by_default() {
+        let (selected, skipped) = RepositorySelection::default().select(
+            vec![repository("one"), repository("two")],
+            &ListingFilter::default(),
+        );
+
+        assert_eq!(names(&selected), ["one", "two"]);
+        assert!(skipped.is_empty());
+    }
+
+    #[test]
+    fn select_with_globs_and_regexes() {
+        let selection = RepositorySelection::new(
+            &["vscode*".to_string(), "re:^docs".to_string()],
+            &["re:-(samples|test)$".to_string()],
+            None,
+            None,
+        )
+        .unwrap();
+
+        let (selected, skipped) = selection.select(
+            vec![
+                repository("vscode"),
+                repository("vscode-samples"),
+                repository("docs-site"),
+                repository("typescript"),
+            ],
+            &ListingFilter::default(),
+        );
+
+        assert_eq!(names(&selected), ["vscode", "docs-site"]);
+        assert_eq!(skipped[0].name, "vscode-samples");
+        assert_eq!(skipped[0].reason, "name excluded");
+        assert_eq!(skipped[1].name, "typescript");
+        assert_eq!(skipped[1].reason, "name not included");
+    }
+
+    #[test]
+    fn select_skips_forks_large_repositories_and_over_the_limit() {
+        let selection = RepositorySelection::new(&[], &[], Some(2), Some(1000)).unwrap();
+        let fork = RepositoryMetadata {
+            fork: true,
+            ..repository("fork")
+        };
+        let large = RepositoryMetadata {
+            size_kb: Some(5000),
+            ..repository("large")
+        };
+        let unknown_size = repository("unknown-size");
+
+        let (selected, skipped) = selection.select(
+            vec![
+                fork,
+                large,
+                unknown_size,
+                repository("small"),
+                repository("last"),
+            ],
+            &ListingFilter::default(),
+        );
+
+        assert_eq!(names(&selected), ["unknown-size", "small"]);
+        let reasons = skipped
+            .iter()
+            .map(|skipped| (skipped.name.as_str(), skipped.reason.as_str()))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            reasons,
+            [
+                ("fork", "fork"),
+                ("large", "size of 5000 KB over the limit of 1000 KB"),
+                ("last", "over the limit of 2 repositories"),
+            ]
+        );
+    }
+
+    #[test]
+    fn select_applies_the_listing_filter() {
+        let archived = RepositoryMetadata {
+            archived: true,
+            ..repository("archived")
+        };
+        let mirror = RepositoryMetadata {
+            mirror: true,
+            ..repository("mirror")
+        };
+        let listing_filter = ListingFilter {
+            include_forks: true,
+            exclude_archived: true,
+            only_mirrors: false,
+        };
+
+        let (selected, skipped) = RepositorySelection::default()
+            .select(vec![archived.clone(), mirror.clone()], &listing_filter);
+        assert_eq!(names(&selected), ["mirror"]);
+        assert_eq!(skipped[0].reason, "archived");
+
+        let listing_filter = ListingFilter {
+            only_mirrors: true,
+            ..ListingFilter::default()
+        };
+        let (selected, skipped) =
+            RepositorySelection::default().select(vec![archived, mirror], &listing_filter);
+        assert_eq!(names(&selected), ["mirror"]);
+        assert_eq!(skipped[0].reason, "not a mirror");
+    }
+
+    #[test]
+    fn invalid_patterns_are_rejected() {
+        assert!(RepositorySelection::new(&["re:(".to_string()], &[], None, None).is_err());
+        assert!(RepositorySelection::new(&[], &["[".to_string()], None, None).is_err());
+    }
+}
@@ -0,0 +1,172 @@
+use async_trait::async_trait;
+use reqwest::{Client, Url};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    config::ApiConfig,
+    forge::{Forge, ForgeError, OwnerKind},
+    graphql::{post_query, GraphqlError, GraphqlRequest},
+    types::RepositoryMetadata,
+};
+
+const OWNER_PREFIX: char = '~';
+
+const REPOSITORIES_QUERY: &str = r#"
+query($username: String!, $cursor: Cursor) {
+  user(username: $username) {
+    repositories(cursor: $cursor) {
+      cursor
+      results {
+        name
+        updated
+        HEAD { name }
+      }
+    }
+  }
+}
+"#;
+
+/// Base url of git.sr.ht, its GraphQL API is served at `<host>/query`
+pub fn api_base_url(url: &Url) -> Url {
+    let mut base_url = url.clone();
+    base_url.set_path("/");
+    base_url.set_query(None);
+    base_url
+}
+
+#[derive(Serialize)]
+struct Variables<'a> {
+    username: &'a str,
+    cursor: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct Data {
+    user: Option<SourcehutUser>,
+}
+
+#[derive(Deserialize)]
+struct SourcehutUser {
+    repositories: RepositoryCursor,
+}
+
+#[derive(Deserialize)]
+struct RepositoryCursor {
+    cursor: Option<String>,
+    results: Vec<SourcehutRepository>,
+}
+
+#[derive(Deserialize)]
+struct SourcehutRepository {
+    name: String,
+    updated: Option<String>,
+    #[serde(rename = "HEAD")]
+    head: Option<Reference>,
+}
+
+/// HEAD is a full reference name like `refs/heads/master`
+#[derive(Deserialize)]
+struct Reference {
+    name: String,
+}
+
+pub struct SourcehutApi<'a> {
+    client: &'a Client,
+    config: &'a ApiConfig,
+    owner_url: &'a Url,
+}
+
+impl<'a> SourcehutApi<'a> {
+    pub fn new(client: &'a Client, config: &'a ApiConfig, owner_url: &'a Url) -> Self {
+        Self {
+            client,
+            config,
+            owner_url,
+        }
+    }
+
+    /// Queries are served at `<host>/query`
+    async fn post(
+        &self,
+        request: &GraphqlRequest<'_, Variables<'_>>,
+    ) -> Result<Data, GraphqlError> {
+        post_query(
+            self.client,
+            self.config.base_url.join("query").unwrap(),
+            self.config.token.as_deref(),
+            request,
+        )
+        .await
+    }
+
+    /// `https://git.sr.ht/~user/repo`, next to the `~user` page
+    fn repository_url(&self, name: &str) -> String {
+        let mut owner_url = self.owner_url.clone();
+        if !owner_url.path().ends_with('/') {
+            owner_url.set_path(&format!("{}/", owner_url.path()));
+        }
+
+        owner_url.join(name).unwrap().to_string()
+    }
+}
+
+#[async_trait]
+impl Forge for SourcehutApi<'_> {
+    /// sourcehut has no organizations
+    async fn owner_kind(&self, _owner: &str) -> Result<OwnerKind, ForgeError> {
+        Ok(OwnerKind::User)
+    }
+
+    /// List the repositories of a `~user`, one cursor page at a time
+    async fn list_repositories(
+        &self,
+        _owner_kind: OwnerKind,
+        owner: &str,
+    ) -> Result<Vec<RepositoryMetadata>, ForgeError> {
+        let username = owner.trim_start_matches(OWNER_PREFIX);
+
+        let mut repositories = Vec::new();
+        let mut cursor: Option<String> = None;
+
+        loop {
+            let request = GraphqlRequest {
+                query: REPOSITORIES_QUERY,
+                variables: Variables {
+                    username,
+                    cursor: cursor.clone(),
+                },
+            };
+
+            let page = self
+                .post(&request)
+                .await?
+                .user
+                .ok_or_else(|| GraphqlError::UnknownOwner(owner.to_string()))?
+                .repositories;
+
+            repositories.extend(page.results.into_iter().map(|repository| {
+                RepositoryMetadata {
+                    url: self.repository_url(&repository.name),
+                    name: repository.name,
+                    default_branch: repository
+                        .head
+                        .map(|head| head.name.trim_start_matches("refs/heads/").to_string()),
+                    pushed_at: repository.updated,
+                    ..Default::default()
+                }
+            }));
+
+            cursor = page.cursor;
+            if cursor.is_none() {
+                break;
+            }
+        }
+
+        log::info!(
+            "sourcehut listed {} repositories for {}",
+            repositories.len(),
+            owner
+        );
+        Ok(repositories)
+    }
+}
@@ -0,0 +1,280 @@
+use crate::{
+    config::{LogConfig, RepositoryConfig},
+    repo::{Repository, RepositoryFactory},
+    target::Target,
+};
+use ahash::{HashMap, HashMapExt};
+use indicatif::MultiProgress;
+use reqwest::Url;
+use std::{
+    collections::HashSet,
+    path::Path,
+    sync::{Arc, Mutex},
+};
+
+const GITMODULES: &str = ".gitmodules";
+
+/// A submodule entry declared in the `.gitmodules` file of a repository HEAD
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Submodule {
+    pub name: String,
+    pub path: String,
+    pub url: String,
+}
+
+impl Submodule {
+    /// Read `.gitmodules` at HEAD of a (bare) clone
+    pub fn list(repo: &git2::Repository) -> Vec<Submodule> {
+        let content = repo
+            .head()
+            .and_then(|head| head.peel_to_tree())
+            .and_then(|tree| tree.get_path(Path::new(GITMODULES)))
+            .and_then(|entry| entry.to_object(repo))
+            .and_then(|object| object.peel_to_blob())
+            .map(|blob| String::from_utf8_lossy(blob.content()).to_string());
+
+        match content {
+            Ok(content) => Self::parse(&content),
+            Err(_) => Vec::new(),
+        }
+    }
+
+    pub fn parse(content: &str) -> Vec<Submodule> {
+        let mut submodules = Vec::new();
+        let mut current: Option<Submodule> = None;
+
+        for line in content.lines().map(str::trim) {
+            if line.is_empty() || line.starts_with('#') || line.starts_with(';') {
+                continue;
+            }
+
+            if line.starts_with('[') {
+                submodules.extend(current.take());
+                current = line
+                    .strip_prefix("[submodule")
+                    .and_then(|rest| rest.strip_suffix(']'))
+                    .map(|name| Submodule {
+                        name: name.trim().trim_matches('"').to_string(),
+                        path: String::new(),
+                        url: String::new(),
+                    });
+                continue;
+            }
+
+            if let (Some(submodule), Some((key, value))) = (current.as_mut(), line.split_once('='))
+            {
+                match key.trim() {
+                    "path" => submodule.path = value.trim().to_string(),
+                    "url" => submodule.url = value.trim().to_string(),
+                    _ => {}
+                }
+            }
+        }
+        submodules.extend(current);
+
+        submodules.retain(|submodule| !submodule.path.is_empty() && !submodule.url.is_empty());
+        submodules
+    }
+
+    /// Resolve the submodule url against the url of its parent repository.
+    /// Relative urls (`./` or `../`) are relative to the parent remote, like git does.
+    /// The path is kept as git computes it, a `.git` suffix is needed by exact path remotes like `file://`.
+    pub fn resolve_url(&self, parent_url: &Url) -> Option<Url> {
+        if self.url.starts_with("./") || self.url.starts_with("../") {
+            let mut base = parent_url.clone();
+            if !base.path().ends_with('/') {
+                base.set_path(&format!("{}/", base.path()));
+            }
+            base.join(&self.url).ok()
+        } else {
+            Target::repository(&self.url)
+                .ok()
+                .map(|target| target.clone_url)
+        }
+    }
+}
+
+/// Key of a repository in the set of scanned urls, with or without `.git` and a trailing slash
+pub(crate) fn visited_key(url: &Url) -> String {
+    let path = url
+        .path()
+        .trim_end_matches('/')
+        .trim_end_matches(".git")
+        .to_string();
+    let mut url = url.clone();
+    url.set_path(&path);
+    url.set_query(None);
+    url.set_fragment(None);
+
+    url.to_string()
+}
+
+/// Clone and scan the submodules of a repository through the same `RepositoryFactory` path.
+/// Each submodule url is scanned once per root repository.
+pub fn scan_submodules(
+    clone_path: &Path,
+    parent_url: &Url,
+    all_branches: bool,
+    log_config: &LogConfig,
+    visited: Arc<Mutex<HashSet<String>>>,
+) -> HashMap<String, Repository> {
+    let mut submodules_data = HashMap::new();
+
+    let repo = match git2::Repository::open_bare(clone_path) {
+        Ok(repo) => repo,
+        Err(_) => return submodules_data,
+    };
+
+    let child_log_config = LogConfig {
+        submodule_depth: log_config.submodule_depth - 1,
+        ..log_config.clone()
+    };
+
+    for submodule in Submodule::list(&repo) {
+        let url = match submodule.resolve_url(parent_url) {
+            Some(url) => url,
+            None => {
+                log::warn!(
+                    "Skip submodule {} : unsupported url {}",
+                    submodule.name,
+                    submodule.url
+                );
+                continue;
+            }
+        };
+
+        if !visited.lock().unwrap().insert(visited_key(&url)) {
+            log::debug!(
+                "Skip submodule {} : {} already scanned",
+                submodule.name,
+                url
+            );
+            continue;
+        }
+
+        log::info!("Scanning submodule {} at {}", submodule.path, url);
+
+        let repository_config = RepositoryConfig::new(url, all_branches, child_log_config.clone());
+        let mpb = Arc::new(Mutex::new(MultiProgress::new()));
+        let child = match RepositoryFactory::with_config(repository_config).create(mpb) {
+            Ok(child) => child,
+            Err(e) => {
+                log::error!("Failed to clone submodule {} : {:?}", submodule.name, e);
+                continue;
+            }
+        };
+
+        let child = child.with_visited_submodules(visited.clone()).extract_log();
+        submodules_data.insert(submodule.path, child);
+    }
+
+    submodules_data
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn submodule(url: &str) -> Submodule {
+        Submodule {
+            name: "lib".to_string(),
+            path: "lib".to_string(),
+            url: url.to_string(),
+        }
+    }
+
+    #[test]
+    fn parse_gitmodules() {
+        let content = r#"
+# vendored libraries
+[submodule "vendor/lib"]
+	path = vendor/lib
+	url = https://github.com/acme/lib.git
+	branch = main
+[submodule "docs"]
+	path = docs
+	url = ../docs
+; no url, ignored
+[submodule "broken"]
+	path = broken
+"#;
+
+        assert_eq!(
+            Submodule::parse(content),
+            vec![
+                Submodule {
+                    name: "vendor/lib".to_string(),
+                    path: "vendor/lib".to_string(),
+                    url: "https://github.com/acme/lib.git".to_string(),
+                },
+                Submodule {
+                    name: "docs".to_string(),
+                    path: "docs".to_string(),
+                    url: "../docs".to_string(),
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn resolve_relative_urls_against_the_parent() {
+        let parent_url = Url::parse("https://github.com/acme/app/").unwrap();
+
+        assert_eq!(
+            submodule("../lib.git")
+                .resolve_url(&parent_url)
+                .unwrap()
+                .as_str(),
+            "https://github.com/acme/lib.git"
+        );
+        assert_eq!(
+            submodule("../../other/lib")
+                .resolve_url(&parent_url)
+                .unwrap()
+                .as_str(),
+            "https://github.com/other/lib"
+        );
+        assert_eq!(
+            submodule("./lib")
+                .resolve_url(&Url::parse("https://github.com/acme/app").unwrap())
+                .unwrap()
+                .as_str(),
+            "https://github.com/acme/app/lib"
+        );
+    }
+
+    #[test]
+    fn resolve_absolute_urls() {
+        let parent_url = Url::parse("https://github.com/acme/app/").unwrap();
+
+        assert_eq!(
+            submodule("git@gitlab.com:group/lib.git")
+                .resolve_url(&parent_url)
+                .unwrap()
+                .as_str(),
+            "ssh://git@gitlab.com/group/lib.git"
+        );
+        assert_eq!(
+            submodule("https://github.com/acme/lib.git")
+                .resolve_url(&parent_url)
+                .unwrap()
+                .as_str(),
+            "https://github.com/acme/lib.git"
+        );
+        assert_eq!(submodule("lib").resolve_url(&parent_url), None);
+    }
+
+    #[test]
+    fn visited_key_ignores_git_suffix_and_trailing_slash() {
+        let key = visited_key(&Url::parse("https://github.com/acme/app").unwrap());
+
+        assert_eq!(
+            visited_key(&Url::parse("https://github.com/acme/app.git").unwrap()),
+            key
+        );
+        assert_eq!(
+            visited_key(&Url::parse("https://github.com/acme/app/?tab=readme#top").unwrap()),
+            key
+        );
+    }
+}
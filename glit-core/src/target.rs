@@ -0,0 +1,302 @@
+use reqwest::Url;
+use std::fmt;
+
+const GIT_SUFFIX: &str = ".git";
+const SUPPORTED_SCHEMES: [&str; 5] = ["https", "http", "ssh", "git", "file"];
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TargetError {
+    InvalidUrl(String),
+    UnsupportedScheme(String),
+    MissingOwner(String),
+    MissingRepository(String),
+}
+
+impl fmt::Display for TargetError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TargetError::InvalidUrl(input) => write!(f, "{} is not a valid url", input),
+            TargetError::UnsupportedScheme(scheme) => write!(f, "unsupported scheme {}", scheme),
+            TargetError::MissingOwner(input) => write!(f, "no owner in {}", input),
+            TargetError::MissingRepository(input) => write!(f, "no repository in {}", input),
+        }
+    }
+}
+
+/// A user, organization or repository url normalized from any of the forms accepted by git :
+/// `https://host/owner/repo(.git)`, `git@host:owner/repo.git`, `ssh://`, `git://` and `file://`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Target {
+    pub scheme: String,
+    /// `None` for `file://` targets
+    pub host: Option<String>,
+    /// Owner path, can contain several segments (ex: gitlab `group/subgroup`)
+    pub owner: String,
+    /// Repository name without `.git`, `None` when the target is a user or an organization
+    pub name: Option<String>,
+    pub clone_url: Url,
+}
+
+impl Target {
+    /// Parse a repository target : the last path segment is the repository, the others are the owner
+    pub fn repository(input: &str) -> Result<Target, TargetError> {
+        let url = Self::normalize(input)?;
+        let mut segments = Self::segments(&url);
+
+        let name = segments
+            .pop()
+            .map(|name| name.trim_end_matches(GIT_SUFFIX).to_string())
+            .filter(|name| !name.is_empty())
+            .ok_or_else(|| TargetError::MissingRepository(input.to_string()))?;
+
+        if segments.is_empty() {
+            return Err(TargetError::MissingOwner(input.to_string()));
+        }
+
+        Ok(Self::build(url, segments.join("/"), Some(name)))
+    }
+
+    /// Parse a user or organization target : the whole path is the owner
+    pub fn owner(input: &str) -> Result<Target, TargetError> {
+        let url = Self::normalize(input)?;
+        let segments = Self::segments(&url);
+
+        if segments.is_empty() {
+            return Err(TargetError::MissingOwner(input.to_string()));
+        }
+
+        Ok(Self::build(url, segments.join("/"), None))
+    }
+
+    /// Web url of the target (`https://host/owner/[repo/]`), `None` for `file://` targets.
+    /// SSH and git protocol targets are served over https.
+    pub fn web_url(&self) -> Option<Url> {
+        let host = self.host.as_ref()?;
+        let scheme = match self.scheme.as_str() {
+            "http" => "http",
+            _ => "https",
+        };
+
+        let port = match (scheme == self.scheme, self.clone_url.port()) {
+            (true, Some(port)) => format!(":{}", port),
+            _ => String::new(),
+        };
+
+        let path = match &self.name {
+            Some(name) => format!("{}/{}/", self.owner, name),
+            None => format!("{}/", self.owner),
+        };
+
+        Url::parse(&format!("{}://{}{}/{}", scheme, host, port, path)).ok()
+    }
+
+    fn build(url: Url, owner: String, name: Option<String>) -> Target {
+        let mut clone_url = url.clone();
+        let path = clone_url.path().trim_end_matches('/').to_string();
+        clone_url.set_path(&path);
+        clone_url.set_query(None);
+        clone_url.set_fragment(None);
+
+        Target {
+            scheme: url.scheme().to_string(),
+            host: url.host_str().map(str::to_string),
+            owner,
+            name,
+            clone_url,
+        }
+    }
+
+    fn segments(url: &Url) -> Vec<String> {
+        url.path_segments()
+            .map(|segments| {
+                segments
+                    .filter(|segment| !segment.is_empty())
+                    .map(str::to_string)
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+
+    /// Turn scp-like `[user@]host:path` into `ssh://[user@]host/path` and check the scheme
+    fn normalize(input: &str) -> Result<Url, TargetError> {
+        let input = input.trim();
+
+        let url = match Self::scp_like(input) {
+            Some((authority, path)) => format!("ssh://{}/{}", authority, path),
+            None => input.to_string(),
+        };
+
+        let url = Url::parse(&url).map_err(|_| TargetError::InvalidUrl(input.to_string()))?;
+        if !SUPPORTED_SCHEMES.contains(&url.scheme()) {
+            return Err(TargetError::UnsupportedScheme(url.scheme().to_string()));
+        }
+
+        Ok(url)
+    }
+
+    fn scp_like(input: &str) -> Option<(&str, &str)> {
+        if input.contains("://") {
+            return None;
+        }
+
+        let (authority, path) = input.split_once(':')?;
+        if authority.is_empty() || authority.contains('/') {
+            return None;
+        }
+
+        Some((authority, path.trim_start_matches('/')))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn repository_from_https_url() {
+        let target = Target::repository("https://github.com/rust-lang/git2-rs").unwrap();
+
+        assert_eq!(target.scheme, "https");
+        assert_eq!(target.host.as_deref(), Some("github.com"));
+        assert_eq!(target.owner, "rust-lang");
+        assert_eq!(target.name.as_deref(), Some("git2-rs"));
+        assert_eq!(
+            target.clone_url.as_str(),
+            "https://github.com/rust-lang/git2-rs"
+        );
+    }
+
+    #[test]
+    fn repository_strips_git_suffix_and_trailing_slash() {
+        let target = Target::repository("https://github.com/rust-lang/git2-rs.git/").unwrap();
+
+        assert_eq!(target.name.as_deref(), Some("git2-rs"));
+        assert_eq!(
+            target.clone_url.as_str(),
+            "https://github.com/rust-lang/git2-rs.git"
+        );
+    }
+
+    #[test]
+    fn repository_from_scp_like_url() {
+        let target = Target::repository("git@github.com:rust-lang/git2-rs.git").unwrap();
+
+        assert_eq!(target.scheme, "ssh");
+        assert_eq!(target.host.as_deref(), Some("github.com"));
+        assert_eq!(target.owner, "rust-lang");
+        assert_eq!(target.name.as_deref(), Some("git2-rs"));
+        assert_eq!(
+            target.clone_url.as_str(),
+            "ssh://git@github.com/rust-lang/git2-rs.git"
+        );
+    }
+
+    #[test]
+    fn repository_from_ssh_url_with_port() {
+        let target =
+            Target::repository("ssh://git@git.example.com:2222/group/sub/repo.git").unwrap();
+
+        assert_eq!(target.host.as_deref(), Some("git.example.com"));
+        assert_eq!(target.owner, "group/sub");
+        assert_eq!(target.name.as_deref(), Some("repo"));
+        assert_eq!(target.clone_url.port(), Some(2222));
+        assert_eq!(
+            target.web_url().unwrap().as_str(),
+            "https://git.example.com/group/sub/repo/"
+        );
+    }
+
+    #[test]
+    fn repository_from_git_url() {
+        let target = Target::repository("git://git.kernel.org/pub/scm/git/git.git").unwrap();
+
+        assert_eq!(target.scheme, "git");
+        assert_eq!(target.owner, "pub/scm/git");
+        assert_eq!(target.name.as_deref(), Some("git"));
+        assert_eq!(
+            target.web_url().unwrap().as_str(),
+            "https://git.kernel.org/pub/scm/git/git/"
+        );
+    }
+
+    #[test]
+    fn repository_from_file_url() {
+        let target = Target::repository("file:///tmp/repos/project").unwrap();
+
+        assert_eq!(target.scheme, "file");
+        assert_eq!(target.host, None);
+        assert_eq!(target.owner, "tmp/repos");
+        assert_eq!(target.name.as_deref(), Some("project"));
+        assert_eq!(target.web_url(), None);
+    }
+
+    #[test]
+    fn repository_without_owner_is_rejected() {
+        assert_eq!(
+            Target::repository("https://github.com/git2-rs"),
+            Err(TargetError::MissingOwner(
+                "https://github.com/git2-rs".to_string()
+            ))
+        );
+        assert_eq!(
+            Target::repository("https://github.com/"),
+            Err(TargetError::MissingRepository(
+                "https://github.com/".to_string()
+            ))
+        );
+    }
+
+    #[test]
+    fn unsupported_or_invalid_urls_are_rejected() {
+        assert_eq!(
+            Target::repository("ftp://example.com/owner/repo"),
+            Err(TargetError::UnsupportedScheme("ftp".to_string()))
+        );
+        assert_eq!(
+            Target::owner("not a url"),
+            Err(TargetError::InvalidUrl("not a url".to_string()))
+        );
+    }
+
+    #[test]
+    fn owner_keeps_the_whole_path() {
+        let target = Target::owner("https://gitlab.com/gitlab-org/security-products/").unwrap();
+
+        assert_eq!(target.owner, "gitlab-org/security-products");
+        assert_eq!(target.name, None);
+        assert_eq!(
+            target.web_url().unwrap().as_str(),
+            "https://gitlab.com/gitlab-org/security-products/"
+        );
+    }
+
+    #[test]
+    fn owner_from_scp_like_url() {
+        let target = Target::owner("git@github.com:netflix").unwrap();
+
+        assert_eq!(target.scheme, "ssh");
+        assert_eq!(target.owner, "netflix");
+        assert_eq!(
+            target.web_url().unwrap().as_str(),
+            "https://github.com/netflix/"
+        );
+    }
+
+    #[test]
+    fn owner_keeps_the_port_of_http_urls() {
+        let target = Target::owner("http://127.0.0.1:8765/alice").unwrap();
+
+        assert_eq!(
+            target.web_url().unwrap().as_str(),
+            "http://127.0.0.1:8765/alice/"
+        );
+    }
+
+    #[test]
+    fn owner_without_path_is_rejected() {
+        assert_eq!(
+            Target::owner("https://github.com"),
+            Err(TargetError::MissingOwner("https://github.com".to_string()))
+        );
+    }
+}
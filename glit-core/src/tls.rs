@@ -0,0 +1,56 @@
+use reqwest::Certificate;
+use std::{
+    ffi::CString,
+    fmt, fs,
+    os::raw::{c_char, c_int},
+    path::Path,
+    ptr,
+};
+
+#[derive(Debug)]
+pub enum TlsError {
+    Io(std::io::Error),
+    Certificate(reqwest::Error),
+    Git(git2::Error),
+}
+
+impl fmt::Display for TlsError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TlsError::Io(e) => write!(f, "cannot read the certificate : {}", e),
+            TlsError::Certificate(e) => write!(f, "invalid PEM certificate : {}", e),
+            TlsError::Git(e) => write!(f, "libgit2 rejected the certificate : {}", e.message()),
+        }
+    }
+}
+
+/// Load a PEM CA certificate, for example the one of a GitHub Enterprise Server, to add to the `reqwest` client
+pub fn load_ca_certificate(path: &Path) -> Result<Certificate, TlsError> {
+    let pem = fs::read(path).map_err(TlsError::Io)?;
+    Certificate::from_pem(&pem).map_err(TlsError::Certificate)
+}
+
+/// Trust a PEM CA certificate for every libgit2 https clone of the process, on top of the system store
+pub fn trust_ca_certificate_for_git(path: &Path) -> Result<(), TlsError> {
+    let file = path
+        .to_str()
+        .and_then(|path| CString::new(path).ok())
+        .ok_or_else(|| TlsError::Git(git2::Error::from_str("invalid certificate path")))?;
+
+    libgit2_sys::init();
+    let code = unsafe {
+        libgit2_sys::git_libgit2_opts(
+            libgit2_sys::GIT_OPT_SET_SSL_CERT_LOCATIONS as c_int,
+            file.as_ptr(),
+            ptr::null::<c_char>(),
+        )
+    };
+
+    if code < 0 {
+        let e = git2::Error::last_error(code)
+            .unwrap_or_else(|| git2::Error::from_str("cannot set the certificate location"));
+        return Err(TlsError::Git(e));
+    }
+
+    Ok(())
+}
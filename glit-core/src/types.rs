@@ -1,4 +1,6 @@
+use reqwest::Url;
 use serde::{Deserialize, Serialize};
+use std::fmt;
 
 #[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub struct Branch(pub String);
@@ -6,32 +8,57 @@ pub struct Branch(pub String);
 #[derive(Debug, Clone, PartialEq, Eq, Ord, Hash, PartialOrd, Serialize, Deserialize)]
 pub struct AuthorName(pub String);
 
-impl ToString for AuthorName {
-    fn to_string(&self) -> String {
-        self.0.to_string()
+impl fmt::Display for AuthorName {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.0)
     }
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub struct RepoName(pub String);
-impl ToString for RepoName {
-    fn to_string(&self) -> String {
-        self.0.to_string()
+impl fmt::Display for RepoName {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.0)
     }
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub struct BranchName(pub String);
-impl ToString for BranchName {
-    fn to_string(&self) -> String {
-        self.0.to_string()
+impl fmt::Display for BranchName {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.0)
     }
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub struct Mail(pub String);
-impl ToString for Mail {
-    fn to_string(&self) -> String {
-        self.0.to_string()
+impl fmt::Display for Mail {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+/// What a listing backend knows about a repository before cloning it
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RepositoryMetadata {
+    pub name: String,
+    pub url: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub default_branch: Option<String>,
+    pub fork: bool,
+    pub archived: bool,
+    /// Pull mirror of a repository hosted elsewhere
+    #[serde(default)]
+    pub mirror: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub size_kb: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub pushed_at: Option<String>,
+}
+
+impl RepositoryMetadata {
+    /// Repository url in the `<host>/<owner>/<repo>/` shape expected by `RepositoryFactory`
+    pub fn repo_url(&self) -> Url {
+        Url::parse(&format!("{}/", self.url.trim_end_matches('/'))).unwrap()
     }
 }
@@ -1,30 +1,76 @@
 use ahash::RandomState;
 use async_trait::async_trait;
 use dashmap::DashMap;
+use indicatif::MultiProgress;
+use rayon::prelude::{IntoParallelRefIterator, ParallelIterator};
 use reqwest::{Client, Url};
-use scraper::{Html, Selector};
 use serde::Serialize;
+use std::sync::{Arc, Mutex};
 
-use crate::{config::UserConfig, repo::Repository, types::RepoName, ExtractLog, Factory};
+use crate::{
+    config::{
+        ApiConfig, Backend, ForgeKind, ListingFilter, LogConfig, RepositoryConfig, UserConfig,
+    },
+    contributions::Identities,
+    extract_repositories, extract_repositories_keyed,
+    forge::{forge_for, ForgeError, OwnerKind, TargetKind},
+    gist::list_gists,
+    listing::Listing,
+    repo::{Repository, RepositoryFactory},
+    selection::{RepositorySelection, SelectedRepositories, SkippedRepository},
+    target::Target,
+    types::{RepoName, RepositoryMetadata},
+    ExtractLog,
+};
 
-#[derive(Serialize)]
+#[derive(Debug, Clone, Serialize)]
 pub struct User {
     pub name: String,
     #[serde(skip)]
     pub url: Url,
     pub repo_count: usize,
     #[serde(skip)]
-    pub pages_urls: Vec<Url>,
+    pub repo_urls: Vec<Url>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub repositories_metadata: Vec<RepositoryMetadata>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub skipped_repositories: Vec<SkippedRepository>,
     #[serde(skip)]
     pub all_branches: bool,
+    #[serde(skip)]
+    pub log_config: LogConfig,
+    #[serde(skip)]
+    pub gists: bool,
+    #[serde(skip)]
+    pub forge: ForgeKind,
+    #[serde(skip)]
+    pub api_config: ApiConfig,
     pub repositories_data: DashMap<RepoName, Repository, RandomState>,
+    #[serde(skip)]
+    pub contribution_urls: Vec<Url>,
+    /// Repositories of other owners the user contributed to, in the order of `contribution_urls`
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub contributions_metadata: Vec<RepositoryMetadata>,
+    /// Names and emails kept in the contributions, `None` when contributions are not scanned
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub identities: Option<Identities>,
+    /// Contributions keyed by `owner/repository`, with only the committers matching `identities`
+    #[serde(skip_serializing_if = "DashMap::is_empty")]
+    pub contributions_data: DashMap<RepoName, Repository, RandomState>,
 }
 
 pub struct UserFactory {
     url: Url,
     name: String,
-    page_url: Url,
     all_branches: bool,
+    log_config: LogConfig,
+    forge: ForgeKind,
+    backend: Backend,
+    listing_filter: ListingFilter,
+    selection: RepositorySelection,
+    api_config: ApiConfig,
+    gists: bool,
+    contributions: bool,
 }
 
 impl UserFactory {
@@ -32,89 +78,223 @@ impl UserFactory {
         // CLI param
         let url = user_config.url;
         let all_branches: bool = user_config.all_branches;
+        let log_config = user_config.log_config;
+        let forge = user_config.forge;
+        let backend = user_config.backend;
+        let api_config = user_config.api_config;
+        let listing_filter = user_config.listing_filter;
+        let selection = user_config.selection;
+        let gists = user_config.gists;
+        let contributions = user_config.contributions;
 
         // Craft other param
-        let mut path_segment = url.path_segments().unwrap();
-        let name = path_segment.next().unwrap().to_string();
-
-        let page_url = format!("{}?tab=repositories&type=source", url);
-        let page_url = Url::parse(&page_url).unwrap();
+        let name = Target::owner(url.as_str())
+            .unwrap_or_else(|e| panic!("Invalid user url : {}", e))
+            .owner;
 
         UserFactory {
             url,
             name,
-            page_url,
             all_branches,
+            log_config,
+            forge,
+            backend,
+            api_config,
+            listing_filter,
+            selection,
+            gists,
+            contributions,
         }
     }
 
+    /// Panics when the repositories of the user can not be listed
     pub async fn build_with_client(self, client: &Client) -> User {
-        let repo_count = Self::_repositories_count(client, self.page_url.clone()).await;
-        let pages_count = Self::_pages_count(repo_count);
-        let pages_urls = Self::_build_repo_links(self.page_url, repo_count, pages_count);
+        let name = self.name.clone();
+        self.try_build_with_client(client)
+            .await
+            .unwrap_or_else(|e| panic!("Failed to list repositories of {} : {}", name, e))
+    }
+
+    /// List the repositories of the user, and its contributions when asked.
+    /// Failing to list contributions is only logged.
+    pub async fn try_build_with_client(self, client: &Client) -> Result<User, ForgeError> {
+        let (mut repositories, contributions, identities) = {
+            let forge = forge_for(
+                self.forge,
+                self.backend,
+                client,
+                &self.api_config,
+                &self.url,
+                self.listing_filter,
+            );
+            let listed = forge.list_repositories(OwnerKind::User, &self.name).await?;
+            let repositories =
+                self.selection
+                    .select_listed(forge.as_ref(), listed, &self.listing_filter);
+
+            // Only the REST API lists contributions, whatever backend listed the repositories
+            let contributions_forge = forge_for(
+                self.forge,
+                Backend::Api,
+                client,
+                &self.api_config,
+                &self.url,
+                self.listing_filter,
+            );
+            let (contributions, identities) = match self.contributions {
+                true => {
+                    let listed = contributions_forge
+                        .list_contributions(&self.name)
+                        .await
+                        .unwrap_or_else(|e| {
+                            log::warn!("Failed to list contributions of {} : {}", self.name, e);
+                            Vec::new()
+                        });
+                    let contributions = self.selection.select_listed(
+                        contributions_forge.as_ref(),
+                        listed,
+                        &self.listing_filter,
+                    );
 
-        User {
+                    let identities = contributions_forge
+                        .known_identities(&self.name)
+                        .await
+                        .unwrap_or_else(|e| {
+                            log::warn!("Failed to fetch the profile of {} : {}", self.name, e);
+                            Identities::new(&self.name)
+                        });
+
+                    (contributions, Some(identities))
+                }
+                false => (SelectedRepositories::default(), None),
+            };
+
+            (repositories, contributions, identities)
+        };
+        repositories.skipped.extend(contributions.skipped);
+        let repo_count = repositories.urls.len();
+
+        Ok(User {
             name: self.name,
             url: self.url,
             repo_count,
-            pages_urls,
+            repo_urls: repositories.urls,
+            repositories_metadata: repositories.metadata,
+            skipped_repositories: repositories.skipped,
             all_branches: self.all_branches,
+            log_config: self.log_config,
+            gists: self.gists,
+            forge: self.forge,
+            api_config: self.api_config,
             repositories_data: DashMap::<_, _, RandomState>::with_capacity_and_hasher(
                 repo_count,
                 RandomState::new(),
             ),
-        }
+            contribution_urls: contributions.urls,
+            contributions_metadata: contributions.metadata,
+            identities,
+            contributions_data: DashMap::default(),
+        })
     }
 }
 
-#[async_trait]
-impl Factory for UserFactory {
-    async fn _repositories_count(client: &Client, url: Url) -> usize {
-        let resp = client.get(url).send().await.unwrap();
-        let text = resp.text().await.unwrap();
-
-        let parser = Html::parse_document(&text);
-        let selector_repositories_count =
-            Selector::parse(r#"div > div.user-repo-search-results-summary > strong:nth-child(1)"#)
-                .unwrap();
-
-        let repository_count_str = parser
-            .select(&selector_repositories_count)
-            .next()
-            .unwrap()
-            .inner_html();
-
-        repository_count_str
-            .trim()
-            .replace(',', "")
-            .parse::<usize>()
-            .unwrap()
+impl User {
+    /// Repositories that would be cloned, without cloning them. Gists are not listed.
+    pub fn listing(&self) -> Listing {
+        let repo_urls = [self.repo_urls.as_slice(), &self.contribution_urls].concat();
+        let repositories_metadata = [
+            self.repositories_metadata.as_slice(),
+            &self.contributions_metadata,
+        ]
+        .concat();
+
+        Listing::new(
+            &self.name,
+            TargetKind::User,
+            &repo_urls,
+            &repositories_metadata,
+            &self.skipped_repositories,
+        )
     }
-}
 
-#[async_trait]
-impl ExtractLog for User {
-    async fn extract_log(mut self, client: &Client) -> Self {
-        let user_selector =
-            Selector::parse(r#"turbo-frame > div > div > ul > li > div > div > h3 > a"#).unwrap();
+    /// Scan the contributions once the own repositories taught which identities are the user's
+    fn extract_contributions_log(&mut self) {
+        let Some(identities) = self.identities.as_mut() else {
+            return;
+        };
 
-        self.repositories_data = Self::common_log_feature(&self, client, user_selector).await;
-        self
-    }
+        let own_repositories = self.repositories_data.iter().collect::<Vec<_>>();
+        identities.learn_from(own_repositories.iter().map(|entry| entry.value()));
+        drop(own_repositories);
 
-    fn get_repo_count(&self) -> usize {
-        self.repo_count
-    }
+        let contributions_data = extract_repositories_keyed(
+            &self.contribution_urls,
+            &self.contributions_metadata,
+            self.all_branches,
+            &self.log_config,
+            |repo| format!("{}/{}", repo.owner, repo.name),
+        );
+        for mut entry in contributions_data.iter_mut() {
+            identities.retain_matching(entry.value_mut());
+        }
 
-    fn get_all_branches(&self) -> bool {
-        self.all_branches
+        self.contributions_data = contributions_data;
     }
 
-    fn get_url(&self) -> Url {
-        self.url.clone()
+    async fn extract_gists_log(&mut self, client: &Client) {
+        let gists = list_gists(client, &self.api_config, &self.name).await;
+        log::info!("Found {} gists for {}", gists.len(), self.name);
+
+        let gists_data = gists
+            .par_iter()
+            .filter_map(|gist| {
+                let clone_url = gist.clone_url(&self.name)?;
+                let repository_config =
+                    RepositoryConfig::new(clone_url, false, self.log_config.clone());
+                let mpb = Arc::new(Mutex::new(MultiProgress::new()));
+
+                match RepositoryFactory::with_config(repository_config).create(mpb) {
+                    Ok(repository) => Some((RepoName(gist.repo_name()), repository.extract_log())),
+                    Err(e) => {
+                        log::error!("Failed to clone gist {} : {:?}", gist.id, e);
+                        None
+                    }
+                }
+            })
+            .collect::<Vec<_>>();
+
+        self.repo_count += gists_data.len();
+        for (repo_name, data) in gists_data {
+            self.repositories_data.insert(repo_name, data);
+        }
     }
+}
+
+#[async_trait]
+impl ExtractLog for User {
+    async fn extract_log(mut self, client: &Client) -> Self {
+        self.repositories_data = extract_repositories(
+            &self.repo_urls,
+            &self.repositories_metadata,
+            &self.name,
+            self.all_branches,
+            &self.log_config,
+        );
 
-    fn get_pages_url(&self) -> Vec<Url> {
-        self.pages_urls.clone()
+        if self.gists {
+            match self.forge {
+                ForgeKind::Github => self.extract_gists_log(client).await,
+                ForgeKind::Gitlab
+                | ForgeKind::Gitea
+                | ForgeKind::Bitbucket
+                | ForgeKind::Sourcehut => {
+                    log::warn!("Gists are only available on github")
+                }
+            }
+        }
+
+        self.extract_contributions_log();
+
+        self
     }
 }
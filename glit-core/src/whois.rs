@@ -0,0 +1,378 @@
+use reqwest::{Client, StatusCode, Url};
+use serde::{Deserialize, Serialize};
+use std::{
+    cmp::Reverse,
+    collections::{BTreeMap, BTreeSet},
+};
+
+use crate::{
+    api::GithubApi,
+    config::{ListingFilter, WhoisConfig},
+    forge::ForgeError,
+    repo::is_false,
+};
+
+pub const GRAVATAR_URL: &str = "https://en.gravatar.com/";
+
+/// Commit as returned by the `/search/commits` endpoint
+#[derive(Debug, Clone, Deserialize)]
+struct SearchCommit {
+    sha: String,
+    html_url: String,
+    commit: CommitDetails,
+    /// Account the author email is attached to, `None` when the email belongs to no account
+    author: Option<SearchAccount>,
+    repository: SearchRepository,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+struct CommitDetails {
+    message: String,
+    author: CommitAuthor,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+struct CommitAuthor {
+    name: String,
+    date: Option<String>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+struct SearchAccount {
+    login: String,
+    html_url: String,
+    avatar_url: Option<String>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+struct SearchRepository {
+    full_name: String,
+}
+
+/// Profile as returned by `{gravatar}/{hash}.json`
+#[derive(Debug, Clone, Deserialize)]
+struct GravatarProfiles {
+    entry: Vec<GravatarProfile>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct GravatarProfile {
+    preferred_username: Option<String>,
+    profile_url: Option<String>,
+    #[serde(default)]
+    accounts: Vec<GravatarAccount>,
+}
+
+/// Account the owner of a gravatar linked to its profile
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct GravatarAccount {
+    pub domain: String,
+    pub username: String,
+    pub url: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct Gravatar {
+    /// md5 of the trimmed lowercase email
+    pub hash: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub preferred_username: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub profile_url: Option<String>,
+    pub accounts: Vec<GravatarAccount>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct SampleCommit {
+    pub repository: String,
+    pub sha: String,
+    pub url: String,
+    pub author_name: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub date: Option<String>,
+    /// First line of the commit message
+    pub message: String,
+}
+
+impl From<&SearchCommit> for SampleCommit {
+    fn from(commit: &SearchCommit) -> Self {
+        Self {
+            repository: commit.repository.full_name.clone(),
+            sha: commit.sha.clone(),
+            url: commit.html_url.clone(),
+            author_name: commit.commit.author.name.clone(),
+            date: commit.commit.author.date.clone(),
+            message: commit
+                .commit
+                .message
+                .lines()
+                .next()
+                .unwrap_or_default()
+                .to_string(),
+        }
+    }
+}
+
+/// Account the forge attached commits of the email to
+#[derive(Debug, Clone, Serialize)]
+pub struct WhoisAccount {
+    pub login: String,
+    pub url: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub avatar_url: Option<String>,
+    pub commit_count: usize,
+    pub repositories: BTreeSet<String>,
+    pub sample_commits: Vec<SampleCommit>,
+    /// The gravatar of the email links to this account
+    #[serde(skip_serializing_if = "is_false")]
+    pub gravatar_linked: bool,
+}
+
+/// Accounts, repositories and author names found for an email, accounts with the most commits first
+#[derive(Debug, Clone, Serialize)]
+pub struct Whois {
+    pub email: String,
+    pub commit_count: usize,
+    pub author_names: BTreeSet<String>,
+    pub accounts: Vec<WhoisAccount>,
+    /// Commits of the email attached to no account
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub unattached_commits: Vec<SampleCommit>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub gravatar: Option<Gravatar>,
+}
+
+impl Whois {
+    /// Search the commits authored with the email through the GitHub REST API
+    pub async fn lookup(client: &Client, config: &WhoisConfig) -> Result<Whois, ForgeError> {
+        let api = GithubApi::new(client, &config.api_config, ListingFilter::default());
+
+        let mut search_url = api.endpoint("search/commits");
+        search_url
+            .query_pairs_mut()
+            .append_pair("q", &format!("author-email:{}", config.email));
+        let commits = api
+            .search::<SearchCommit>(search_url, Some(config.max_commits))
+            .await?;
+        log::info!("Found {} commits of {}", commits.len(), config.email);
+
+        let mut accounts: BTreeMap<String, WhoisAccount> = BTreeMap::new();
+        let mut unattached_commits = Vec::new();
+        for commit in &commits {
+            let Some(author) = &commit.author else {
+                if unattached_commits.len() < config.sample_size {
+                    unattached_commits.push(SampleCommit::from(commit));
+                }
+                continue;
+            };
+
+            let account = accounts
+                .entry(author.login.clone())
+                .or_insert_with(|| WhoisAccount {
+                    login: author.login.clone(),
+                    url: author.html_url.clone(),
+                    avatar_url: author.avatar_url.clone(),
+                    commit_count: 0,
+                    repositories: BTreeSet::new(),
+                    sample_commits: Vec::new(),
+                    gravatar_linked: false,
+                });
+            account.commit_count += 1;
+            account
+                .repositories
+                .insert(commit.repository.full_name.clone());
+            if account.sample_commits.len() < config.sample_size {
+                account.sample_commits.push(SampleCommit::from(commit));
+            }
+        }
+
+        let gravatar = match &config.gravatar_url {
+            Some(gravatar_url) => gravatar(client, gravatar_url, &config.email).await,
+            None => None,
+        };
+
+        let mut accounts = accounts.into_values().collect::<Vec<WhoisAccount>>();
+        if let Some(gravatar) = &gravatar {
+            for account in accounts.iter_mut() {
+                account.gravatar_linked = gravatar.links(account);
+            }
+        }
+        accounts.sort_by_key(|account| Reverse(account.commit_count));
+
+        Ok(Whois {
+            email: config.email.clone(),
+            commit_count: commits.len(),
+            author_names: commits
+                .iter()
+                .map(|commit| commit.commit.author.name.clone())
+                .collect(),
+            accounts,
+            unattached_commits,
+            gravatar,
+        })
+    }
+}
+
+impl Gravatar {
+    /// The gravatar profile lists the account, on the same host
+    fn links(&self, account: &WhoisAccount) -> bool {
+        let host = Url::parse(&account.url)
+            .ok()
+            .and_then(|url| url.host_str().map(str::to_string));
+
+        self.accounts.iter().any(|linked| {
+            linked.username.eq_ignore_ascii_case(&account.login)
+                && host
+                    .as_deref()
+                    .is_some_and(|host| linked.domain.eq_ignore_ascii_case(host))
+        })
+    }
+}
+
+/// Gravatar profile of the email, a missing profile or a failed request gives `None`
+async fn gravatar(client: &Client, gravatar_url: &Url, email: &str) -> Option<Gravatar> {
+    let hash = format!("{:x}", md5::compute(email.trim().to_lowercase()));
+    let url = gravatar_url.join(&format!("{}.json", hash)).unwrap();
+
+    let resp = match client.get(url).send().await {
+        Ok(resp) if resp.status() == StatusCode::NOT_FOUND => {
+            log::info!("No gravatar for {}", email);
+            return None;
+        }
+        Ok(resp) => resp,
+        Err(e) => {
+            log::warn!("Failed to fetch the gravatar of {} : {}", email, e);
+            return None;
+        }
+    };
+
+    let profiles = match resp.error_for_status() {
+        Ok(resp) => resp.json::<GravatarProfiles>().await,
+        Err(e) => Err(e),
+    };
+    match profiles {
+        Ok(profiles) => {
+            let profile = profiles.entry.into_iter().next()?;
+            Some(Gravatar {
+                hash,
+                preferred_username: profile.preferred_username,
+                profile_url: profile.profile_url,
+                accounts: profile.accounts,
+            })
+        }
+        Err(e) => {
+            log::warn!("Failed to fetch the gravatar of {} : {}", email, e);
+            None
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        config::{ApiConfig, ForgeKind},
+        mock::{self, MockPage},
+    };
+
+    const EMAIL: &str = "Alice@Example.com";
+
+    fn commit(sha: &str, name: &str, login: Option<&str>, repository: &str) -> String {
+        let author = match login {
+            Some(login) => format!(
+                r#"{{"login":"{0}","html_url":"https://github.com/{0}","avatar_url":null}}"#,
+                login
+            ),
+            None => "null".to_string(),
+        };
+
+        format!(
+            r#"{{"sha":"{0}","html_url":"https://github.com/{3}/commit/{0}","commit":{{"message":"{0} subject\n\nbody","author":{{"name":"{1}","date":"2024-01-01T00:00:00Z"}}}},"author":{2},"repository":{{"full_name":"{3}"}}}}"#,
+            sha, name, author, repository
+        )
+    }
+
+    fn search_page(commits: &[String]) -> String {
+        format!(r#"{{"total_count":4,"items":[{}]}}"#, commits.join(","))
+    }
+
+    #[tokio::test]
+    async fn lookup_groups_commits_by_account_and_links_the_gravatar() {
+        let gravatar_hash = format!("{:x}", md5::compute("alice@example.com"));
+        let (base_url, server) = mock::serve(vec![
+            MockPage::new(
+                &search_page(&[
+                    commit("c1", "Alice", Some("alice"), "alice/dotfiles"),
+                    commit("c2", "alice", None, "acme/legacy"),
+                ]),
+                Some("api/v3/search/commits?page=2"),
+            ),
+            MockPage::new(
+                &search_page(&[
+                    commit("c3", "Alice W.", Some("alice"), "acme/app"),
+                    commit("c4", "Bob", Some("bob"), "bob/tools"),
+                ]),
+                None,
+            ),
+            MockPage::new(
+                r#"{"entry":[{"preferredUsername":"alice","profileUrl":"https://gravatar.com/alice","accounts":[{"domain":"github.com","username":"alice","url":"https://github.com/alice"}]}]}"#,
+                None,
+            ),
+        ]);
+
+        let target_url = Url::parse("https://github.com/").unwrap();
+        let config = WhoisConfig {
+            email: EMAIL.to_string(),
+            api_config: ApiConfig::new(
+                ForgeKind::Github,
+                &target_url,
+                Some(base_url.join("api/v3/").unwrap()),
+                None,
+            ),
+            max_commits: 3,
+            sample_size: 1,
+            gravatar_url: Some(base_url.join("gravatar/").unwrap()),
+        };
+
+        let whois = Whois::lookup(&Client::new(), &config).await.unwrap();
+        let requests = server.join().unwrap();
+
+        // The fourth commit is past `max_commits`
+        assert_eq!(whois.commit_count, 3);
+        assert_eq!(
+            whois.author_names,
+            BTreeSet::from([
+                "Alice".to_string(),
+                "alice".to_string(),
+                "Alice W.".to_string()
+            ])
+        );
+
+        assert_eq!(whois.accounts.len(), 1);
+        let account = &whois.accounts[0];
+        assert_eq!(account.login, "alice");
+        assert_eq!(account.commit_count, 2);
+        assert_eq!(
+            account.repositories,
+            BTreeSet::from(["alice/dotfiles".to_string(), "acme/app".to_string()])
+        );
+        assert_eq!(account.sample_commits.len(), 1);
+        assert_eq!(account.sample_commits[0].message, "c1 subject");
+        assert!(account.gravatar_linked);
+
+        assert_eq!(whois.unattached_commits.len(), 1);
+        assert_eq!(whois.unattached_commits[0].sha, "c2");
+        assert_eq!(whois.unattached_commits[0].repository, "acme/legacy");
+
+        let gravatar = whois.gravatar.unwrap();
+        assert_eq!(gravatar.hash, gravatar_hash);
+        assert_eq!(gravatar.preferred_username.as_deref(), Some("alice"));
+
+        assert!(requests[0].starts_with(
+            "GET /api/v3/search/commits?q=author-email%3AAlice%40Example.com&per_page=100 "
+        ));
+        assert!(requests[1].starts_with("GET /api/v3/search/commits?page=2 "));
+        assert!(requests[2].starts_with(&format!("GET /gravatar/{}.json ", gravatar_hash)));
+    }
+}